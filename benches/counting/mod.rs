@@ -0,0 +1,46 @@
+//! An allocator that counts allocated bytes, shared by the benchmarks that
+//! report memory use.
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Instant,
+};
+
+/// Tracks the number of bytes currently allocated, and the peak.
+struct Counting;
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let allocated = ALLOCATED.fetch_add(layout.size(), Ordering::Relaxed) + layout.size();
+        PEAK.fetch_max(allocated, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        ALLOCATED.fetch_sub(layout.size(), Ordering::Relaxed);
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+/// Runs `f`, printing how long it took and how many bytes it allocated.
+///
+/// The retained bytes are negative if `f` frees more than it keeps, like when
+/// it consumes a value.
+pub fn measure<T>(name: &str, f: impl FnOnce() -> T) -> T {
+    let before = ALLOCATED.load(Ordering::Relaxed);
+    PEAK.store(before, Ordering::Relaxed);
+    let start = Instant::now();
+    let v = f();
+    let elapsed = start.elapsed();
+    let retained = ALLOCATED.load(Ordering::Relaxed) as isize - before as isize;
+    let peak = PEAK.load(Ordering::Relaxed) - before;
+    println!("{name}: {elapsed:?}, {retained} bytes retained, {peak} bytes at peak");
+    v
+}
@@ -0,0 +1,43 @@
+mod counting;
+
+use counting::measure;
+use json_data::{Options, SharedStringPool, SharedValue, Value};
+
+fn document() -> Vec<u8> {
+    let statuses = ["active", "inactive", "pending", "suspended"];
+    let elements: Vec<_> = (0..20_000)
+        .map(|i| {
+            format!(
+                r#"{{"identifier": {i}, "status": "{}", "category": "repetitive-category-name"}}"#,
+                statuses[i % statuses.len()]
+            )
+        })
+        .collect();
+    format!("[{}]", elements.join(",")).into_bytes()
+}
+
+fn main() {
+    let json = document();
+
+    let value = measure("Value", || Value::from_json(&json).unwrap());
+
+    let options = Options {
+        string_pool: Some(SharedStringPool::default()),
+        ..Options::default()
+    };
+    let shared = measure("SharedValue", || {
+        SharedValue::from_json_with_options(&json, &options).unwrap()
+    });
+
+    assert_eq!(shared.to_value(), value);
+
+    let options = Options {
+        dedup_scalars: true,
+        ..Options::default()
+    };
+    let deduped = measure("SharedValue with dedup_scalars", || {
+        SharedValue::from_json_with_options(&json, &options).unwrap()
+    });
+
+    assert_eq!(deduped, shared);
+}
@@ -0,0 +1,31 @@
+mod counting;
+
+use counting::measure;
+use json_data::Value;
+
+/// A document where most of the memory is in long strings.
+fn document() -> Vec<u8> {
+    let text = "lorem ipsum dolor sit amet ".repeat(20);
+    let elements: Vec<_> = (0..5_000)
+        .map(|i| format!(r#"{{"key-{i}": "{text}", "description": "{text}{i}"}}"#))
+        .collect();
+    format!("[{}]", elements.join(",")).into_bytes()
+}
+
+fn main() {
+    let json = document();
+    let value = Value::from_json(&json).unwrap();
+    let copy = value.clone();
+
+    // Converting an owned value moves the string buffers, so the peak only
+    // grows by the new arrays and maps. Cloning first shows the cost of
+    // copying every string.
+    let moved = measure("Value -> serde_json::Value", || {
+        serde_json::Value::try_from(value).ok().unwrap()
+    });
+    let cloned = measure("&Value -> serde_json::Value (clone)", || {
+        serde_json::Value::try_from(copy.clone()).ok().unwrap()
+    });
+
+    assert_eq!(moved, cloned);
+}
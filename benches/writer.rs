@@ -0,0 +1,44 @@
+use std::{
+    fs::File,
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+use json_data::Value;
+
+fn document() -> Value {
+    let element = Value::from_json(
+        br#"{"id": 12345, "name": "some \"quoted\" name", "tags": ["a", "b", "c"], "score": 0.5}"#,
+    )
+    .unwrap();
+    vec![element; 2000].into()
+}
+
+fn bench(name: &str, mut f: impl FnMut()) {
+    const ITERATIONS: u32 = 20;
+
+    f();
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        f();
+    }
+    let elapsed: Duration = start.elapsed() / ITERATIONS;
+    println!("{name}: {elapsed:?} per iteration");
+}
+
+fn main() {
+    let value = document();
+    let path = std::env::temp_dir().join("json-data-writer-bench.json");
+
+    bench("to_writer (unbuffered file)", || {
+        let file = File::create(&path).unwrap();
+        black_box(&value).to_writer(file).unwrap();
+    });
+
+    bench("to_buffered_writer (file)", || {
+        let file = File::create(&path).unwrap();
+        black_box(&value).to_buffered_writer(file).unwrap();
+    });
+
+    std::fs::remove_file(&path).unwrap();
+}
@@ -1,11 +1,16 @@
 use std::{
-    fmt::Display,
+    fmt::{Debug, Display},
     ops::{Deref, DerefMut},
 };
 
-use crate::{error::Error, parser::Reader, read_value, Value};
+use crate::{
+    error::{Error, ErrorKind},
+    number::{read_number, Number},
+    parser::Reader,
+    read_value, skip_value, Value,
+};
 
-#[derive(Debug, Default, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Default, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Array {
     inner: Vec<Value>,
 }
@@ -15,11 +20,57 @@ impl Array {
         Self::default()
     }
 
+    /// Creates an empty array with room for `capacity` elements.
+    ///
+    /// The other capacity methods of `Vec`, such as `reserve` and
+    /// `shrink_to_fit`, are available through `DerefMut`.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            inner: Vec::with_capacity(capacity),
+        }
+    }
+
     pub fn from_json(bytes: &[u8]) -> Result<Self, Error> {
         Reader::read_all(bytes, read_array)
     }
+
+    /// Parses an array of numbers without building a [`Value`] for each.
+    pub fn from_json_as_f64(bytes: &[u8]) -> Result<Vec<f64>, Error> {
+        Reader::read_all(bytes, |reader| {
+            read_number_array(reader, |n| Ok(n.as_f64()))
+        })
+    }
+
+    /// Parses an array of integers without building a [`Value`] for each.
+    /// Floats, even integral ones like `1.0`, are rejected.
+    pub fn from_json_as_i64(bytes: &[u8]) -> Result<Vec<i64>, Error> {
+        Reader::read_all(bytes, |reader| {
+            read_number_array(reader, |n| n.as_i64().ok_or(ErrorKind::ExpectedI64))
+        })
+    }
+
+    /// Returns the first element matching `f`.
+    pub fn find<F>(&self, mut f: F) -> Option<&Value>
+    where
+        F: FnMut(&Value) -> bool,
+    {
+        self.inner.iter().find(|v| f(v))
+    }
+
+    /// Returns the index of the first element matching `f`.
+    pub fn position<F>(&self, f: F) -> Option<usize>
+    where
+        F: FnMut(&Value) -> bool,
+    {
+        self.inner.iter().position(f)
+    }
 }
 
+/// Gives access to the read-only methods of `Vec` and slices, such as
+/// `binary_search` and `binary_search_by` for arrays kept sorted with
+/// `sort` or [`Options::sort_arrays`].
+///
+/// [`Options::sort_arrays`]: crate::Options::sort_arrays
 impl Deref for Array {
     type Target = Vec<Value>;
 
@@ -28,6 +79,9 @@ impl Deref for Array {
     }
 }
 
+/// Gives access to the in-place methods of `Vec` and slices, such as `swap`,
+/// `rotate_left`, `reverse` and `drain`. Like for `Vec`, these panic on
+/// out-of-range indices.
 impl DerefMut for Array {
     fn deref_mut(&mut self) -> &mut Self::Target {
         &mut self.inner
@@ -40,32 +94,100 @@ impl From<Vec<Value>> for Array {
     }
 }
 
-pub(crate) fn read_array(reader: &mut Reader) -> Result<Array, Error> {
+pub(crate) fn read_array(reader: &mut Reader) -> Result<Array, ErrorKind> {
+    #[cfg(feature = "tracing")]
+    tracing::trace!(offset = reader.offset(), "reading array");
+
+    let mut inner = Vec::new();
+    read_elements(reader, |reader| {
+        let value = read_value(reader)?;
+        reader.allocate(size_of::<Value>())?;
+        inner.push(value);
+        Ok(())
+    })?;
+
+    if reader.options().sort_arrays {
+        inner.sort();
+    }
+
+    Ok(Array { inner })
+}
+
+/// Reads an array of numbers, converting each with `f`.
+fn read_number_array<T>(
+    reader: &mut Reader,
+    mut f: impl FnMut(Number) -> Result<T, ErrorKind>,
+) -> Result<Vec<T>, ErrorKind> {
+    reader.skip_whitespace();
+    let mut inner = Vec::new();
+    read_elements(reader, |reader| {
+        match reader.peek_byte() {
+            None => return Err(ErrorKind::UnexpectedEof),
+            Some(b'-' | b'0'..=b'9') => {}
+            Some(b) => return Err(ErrorKind::ExpectedNumber(b)),
+        }
+        let number = read_number(reader)?;
+        reader.allocate(size_of::<T>())?;
+        inner.push(f(number)?);
+        Ok(())
+    })?;
+    reader.skip_whitespace();
+    Ok(inner)
+}
+
+/// Validates an array like [`read_array`] without building it, returning its
+/// length.
+pub(crate) fn skip_array(reader: &mut Reader) -> Result<usize, ErrorKind> {
+    let mut len = 0;
+    read_elements(reader, |reader| {
+        skip_value(reader)?;
+        len += 1;
+        Ok(())
+    })?;
+    Ok(len)
+}
+
+/// Reads an array, calling `read_element` to read each element.
+///
+/// All the ways of parsing an array go through this, so they accept the same
+/// separators and trailing commas.
+pub(crate) fn read_elements(
+    reader: &mut Reader,
+    mut read_element: impl FnMut(&mut Reader) -> Result<(), ErrorKind>,
+) -> Result<(), ErrorKind> {
     match reader.read_byte()? {
         b'[' => {}
         b => {
-            return Err(Error::ExpectedLeftBracket(b));
+            return Err(ErrorKind::ExpectedLeftBracket(b));
         }
     }
 
     reader.skip_whitespace();
     if reader.peek_byte() == Some(b']') {
         reader.read_byte()?;
-        return Ok(Array::default());
+        return Ok(());
     }
 
-    let mut inner = Vec::new();
     loop {
-        inner.push(read_value(reader)?);
+        reader.skip_whitespace();
+        read_element(reader)?;
+        reader.skip_whitespace();
 
         match reader.read_byte()? {
             b']' => break,
+            b',' if reader.skip_trailing_comma(b']') => break,
             b',' => {}
-            b => return Err(Error::ExpectedCommaOrRightBracket(b)),
+            b => return Err(ErrorKind::ExpectedCommaOrRightBracket(b)),
         }
     }
 
-    Ok(Array { inner })
+    Ok(())
+}
+
+impl Debug for Array {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(&self.inner).finish()
+    }
 }
 
 impl Display for Array {
@@ -90,6 +212,14 @@ impl IntoIterator for Array {
     }
 }
 
+impl FromIterator<Value> for Array {
+    fn from_iter<T: IntoIterator<Item = Value>>(iter: T) -> Self {
+        Self {
+            inner: iter.into_iter().collect(),
+        }
+    }
+}
+
 #[cfg(feature = "serde_json")]
 impl TryFrom<Vec<serde_json::Value>> for Array {
     type Error = crate::InvalidSerdeJsonNumber;
@@ -108,17 +238,17 @@ impl TryFrom<Array> for Vec<serde_json::Value> {
     type Error = crate::InvalidUnicodeString;
 
     fn try_from(value: Array) -> Result<Self, Self::Error> {
-        value
-            .into_iter()
-            .map(serde_json::Value::try_from)
-            .collect::<Result<Vec<_>, _>>()
+        match serde_json::Value::try_from(Value::Array(value))? {
+            serde_json::Value::Array(arr) => Ok(arr),
+            _ => unreachable!(),
+        }
     }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
-    use crate::number::Number;
+    use crate::{number::Number, options::Options};
 
     #[test]
     fn test_empty() {
@@ -148,4 +278,123 @@ mod test {
             .into())
         );
     }
+
+    #[test]
+    fn test_find_and_position() {
+        let arr =
+            Array::from_json(br#"[1, "x", {"id": 1}, {"id": 2, "v": "a"}, {"id": 2}]"#).unwrap();
+        let has_id = |v: &Value| v["id"] == Value::from(2);
+        assert_eq!(arr.position(has_id), Some(3));
+        assert_eq!(arr.find(has_id).map(|v| &v["v"]), Some(&"a".into()));
+        assert_eq!(arr.find(|v| v["id"] == Value::from(3)), None);
+        assert_eq!(arr.position(|v| v == &Value::from("x")), Some(1));
+    }
+
+    #[test]
+    fn test_sort_arrays() {
+        let options = Options {
+            sort_arrays: true,
+            ..Options::default()
+        };
+        assert_eq!(
+            Value::from_json_with_options(b"[3, 1, [2, 1.5], 2]", &options),
+            Value::from_json(b"[1, 2, 3, [1.5, 2]]")
+        );
+        assert_eq!(
+            Value::from_json(b"[3, 1, 2]"),
+            Ok(vec![3.into(), 1.into(), 2.into()].into())
+        );
+    }
+
+    #[test]
+    fn test_capacity() {
+        let mut arr = Array::with_capacity(10);
+        assert!(arr.capacity() >= 10);
+        arr.push(Value::Null);
+        arr.shrink_to_fit();
+        assert!(arr.capacity() < 10);
+        arr.reserve(5);
+        assert!(arr.capacity() >= 6);
+    }
+
+    #[test]
+    fn test_reorder() {
+        let mut arr = Array::from_json(b"[1, 2, 3, 4]").unwrap();
+        arr.swap(0, 3);
+        assert_eq!(arr, Array::from_json(b"[4, 2, 3, 1]").unwrap());
+        arr.rotate_left(1);
+        assert_eq!(arr, Array::from_json(b"[2, 3, 1, 4]").unwrap());
+        arr.reverse();
+        assert_eq!(arr, Array::from_json(b"[4, 1, 3, 2]").unwrap());
+    }
+
+    #[test]
+    fn test_from_json_as_numbers() {
+        assert_eq!(
+            Array::from_json_as_f64(b" [1.5, -2, 3e2 ] "),
+            Ok(vec![1.5, -2.0, 300.0])
+        );
+        assert_eq!(Array::from_json_as_f64(b"[]"), Ok(vec![]));
+        assert_eq!(
+            Array::from_json_as_i64(b"[1, -2, 9223372036854775807]"),
+            Ok(vec![1, -2, i64::MAX])
+        );
+
+        assert_eq!(
+            Array::from_json_as_f64(br#"[1, "2"]"#),
+            Err(Error::at(ErrorKind::ExpectedNumber(b'"'), 4))
+        );
+        assert_eq!(
+            Array::from_json_as_f64(b"[1, null]"),
+            Err(Error::at(ErrorKind::ExpectedNumber(b'n'), 4))
+        );
+        assert_eq!(
+            Array::from_json_as_i64(b"[1, 2.5]"),
+            Err(Error::at(ErrorKind::ExpectedI64, 7))
+        );
+        assert_eq!(
+            Array::from_json_as_i64(b"[1.0]"),
+            Err(Error::at(ErrorKind::ExpectedI64, 4))
+        );
+        assert_eq!(
+            Array::from_json_as_i64(b"[9223372036854775808]"),
+            Err(Error::at(ErrorKind::ExpectedI64, 20))
+        );
+        assert_eq!(
+            Array::from_json_as_f64(b"[1,]"),
+            Err(Error::at(ErrorKind::ExpectedNumber(b']'), 3))
+        );
+        assert_eq!(
+            Array::from_json_as_f64(b"[1"),
+            Err(Error::at(ErrorKind::UnexpectedEof, 2))
+        );
+    }
+
+    #[test]
+    fn test_binary_search() {
+        let mut arr = Array::from_json(br#"[3, "b", null, 1, "a"]"#).unwrap();
+        arr.sort();
+        assert_eq!(arr, Array::from_json(br#"[null, 1, 3, "a", "b"]"#).unwrap());
+        assert_eq!(arr.binary_search(&Value::from(3)), Ok(2));
+        assert_eq!(arr.binary_search(&Value::from("b")), Ok(4));
+        assert_eq!(arr.binary_search(&Value::from(2)), Err(2));
+        assert_eq!(arr.binary_search(&Value::from("c")), Err(5));
+        assert_eq!(arr.binary_search(&Value::Bool(false)), Err(1));
+        assert_eq!(arr.binary_search_by(|v| v.cmp(&"a".into())), Ok(3));
+    }
+
+    #[test]
+    fn test_drain() {
+        let mut arr = Array::from_json(b"[1, 2, 3, 4]").unwrap();
+        let drained: Vec<_> = arr.drain(1..3).collect();
+        assert_eq!(drained, [Value::from(2), Value::from(3)]);
+        assert_eq!(arr, Array::from_json(b"[1, 4]").unwrap());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_swap_out_of_range() {
+        let mut arr = Array::from_json(b"[1, 2]").unwrap();
+        arr.swap(0, 2);
+    }
 }
@@ -0,0 +1,197 @@
+use std::fmt;
+
+use serde::de::{self, Deserialize, Deserializer, MapAccess, SeqAccess, Visitor};
+
+use crate::{Array, JsonString, Number, Object, Value};
+
+/// Deserializes from any self-describing serde format, such as YAML or
+/// MessagePack.
+///
+/// The parts of the serde data model that JSON lacks are mapped as follows:
+/// - Map keys that are `null`, booleans or numbers are converted to strings as
+///   they would be written in JSON, e.g. `1` becomes `"1"`. Other keys are an
+///   error. Like when parsing, the last of several equal keys wins.
+/// - Byte arrays become arrays of numbers.
+/// - Integers that don't fit in a `u64` or `i64` become the nearest float.
+/// - NaN and infinities are an error, unless the `non_finite` feature is
+///   enabled.
+/// - Enums are not supported.
+impl<'de> Deserialize<'de> for Value {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(ValueVisitor)
+    }
+}
+
+struct ValueVisitor;
+
+impl<'de> Visitor<'de> for ValueVisitor {
+    type Value = Value;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("any value")
+    }
+
+    fn visit_bool<E>(self, v: bool) -> Result<Value, E> {
+        Ok(Value::Bool(v))
+    }
+
+    fn visit_i64<E>(self, v: i64) -> Result<Value, E> {
+        Ok(v.into())
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<Value, E> {
+        Ok(v.into())
+    }
+
+    fn visit_i128<E: de::Error>(self, v: i128) -> Result<Value, E> {
+        match (u64::try_from(v), i64::try_from(v)) {
+            (Ok(v), _) => Ok(v.into()),
+            (_, Ok(v)) => Ok(v.into()),
+            _ => self.visit_f64(v as f64),
+        }
+    }
+
+    fn visit_u128<E: de::Error>(self, v: u128) -> Result<Value, E> {
+        match u64::try_from(v) {
+            Ok(v) => Ok(v.into()),
+            Err(_) => self.visit_f64(v as f64),
+        }
+    }
+
+    fn visit_f64<E: de::Error>(self, v: f64) -> Result<Value, E> {
+        #[cfg(feature = "non_finite")]
+        return Ok(Value::Number(Number::from_f64_lossy(v)));
+        #[cfg(not(feature = "non_finite"))]
+        Number::try_from(v)
+            .map(Value::Number)
+            .map_err(|()| E::invalid_value(de::Unexpected::Float(v), &"a finite number"))
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Value, E> {
+        Ok(v.into())
+    }
+
+    fn visit_string<E>(self, v: String) -> Result<Value, E> {
+        Ok(v.into())
+    }
+
+    fn visit_bytes<E>(self, v: &[u8]) -> Result<Value, E> {
+        Ok(v.iter().map(|&b| Value::from(b)).collect())
+    }
+
+    fn visit_none<E>(self) -> Result<Value, E> {
+        Ok(Value::Null)
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<Value, D::Error> {
+        Value::deserialize(deserializer)
+    }
+
+    fn visit_unit<E>(self) -> Result<Value, E> {
+        Ok(Value::Null)
+    }
+
+    fn visit_newtype_struct<D: Deserializer<'de>>(
+        self,
+        deserializer: D,
+    ) -> Result<Value, D::Error> {
+        Value::deserialize(deserializer)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Value, A::Error> {
+        let mut arr = Array::with_capacity(seq.size_hint().unwrap_or(0).min(4096));
+        while let Some(v) = seq.next_element()? {
+            arr.push(v);
+        }
+        Ok(Value::Array(arr))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Value, A::Error> {
+        let mut obj = Object::new();
+        while let Some(key) = map.next_key::<Value>()? {
+            let key = match key {
+                Value::String(s) => s,
+                k @ (Value::Null | Value::Bool(_) | Value::Number(_)) => {
+                    JsonString::from(k.to_string())
+                }
+                Value::Array(_) | Value::Object(_) => {
+                    return Err(de::Error::custom("map key must be a string or scalar"))
+                }
+            };
+            obj.insert(key, map.next_value()?);
+        }
+        Ok(Value::Object(obj))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeMap;
+
+    use super::*;
+
+    #[test]
+    fn test_from_yaml() {
+        let yaml = "
+a: 1
+b: [true, null, -2.5, 'x']
+c:
+  d: ~
+3: three
+false: no
+";
+        let value: Value = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(
+            value,
+            Value::from_json(
+                br#"{"a": 1, "b": [true, null, -2.5, "x"], "c": {"d": null}, "3": "three", "false": "no"}"#
+            )
+            .unwrap()
+        );
+
+        assert!(serde_yaml::from_str::<Value>("[1, 2]: x").is_err());
+    }
+
+    #[cfg(not(feature = "non_finite"))]
+    #[test]
+    fn test_non_finite() {
+        assert!(serde_yaml::from_str::<Value>("[.inf]").is_err());
+        assert!(serde_yaml::from_str::<Value>(".nan").is_err());
+    }
+
+    #[cfg(feature = "non_finite")]
+    #[test]
+    fn test_non_finite() {
+        assert_eq!(
+            serde_yaml::from_str::<Value>("[.inf, -.inf, .nan]").unwrap(),
+            Value::from(vec![
+                Number::infinity().into(),
+                Number::neg_infinity().into(),
+                Number::nan().into(),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_from_msgpack() {
+        let map = BTreeMap::from([(1u8, "a")]);
+        let bytes = rmp_serde::to_vec(&(map, u64::MAX, i64::MIN, 0.5, "s", ())).unwrap();
+        let value: Value = rmp_serde::from_slice(&bytes).unwrap();
+        assert_eq!(
+            value,
+            Value::from(vec![
+                Value::from_json(br#"{"1": "a"}"#).unwrap(),
+                u64::MAX.into(),
+                i64::MIN.into(),
+                Value::from_json(b"0.5").unwrap(),
+                "s".into(),
+                Value::Null,
+            ])
+        );
+
+        // A map from `-1` to the binary `ab`.
+        let bytes = [0x81, 0xff, 0xc4, 0x02, b'a', b'b'];
+        let value: Value = rmp_serde::from_slice(&bytes).unwrap();
+        assert_eq!(value, Value::from_json(br#"{"-1": [97, 98]}"#).unwrap());
+    }
+}
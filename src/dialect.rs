@@ -0,0 +1,220 @@
+use std::fmt::Debug;
+
+/// The lexical rules of a JSON variant, selected with [`Options::dialect`].
+///
+/// Every method defaults to the behavior of strict JSON, as in RFC 8259, so
+/// a custom dialect only overrides what it relaxes.
+///
+/// [`Options::dialect`]: crate::Options::dialect
+pub trait Dialect: Debug + Sync {
+    /// Whether `b` is insignificant whitespace. [`Options::extra_whitespace`]
+    /// is accepted in addition to this.
+    ///
+    /// [`Options::extra_whitespace`]: crate::Options::extra_whitespace
+    fn is_whitespace(&self, b: u8) -> bool {
+        matches!(b, b'\t' | b'\n' | b'\r' | b' ')
+    }
+
+    /// Skip `//` and `/* */` comments like whitespace.
+    fn comments(&self) -> bool {
+        false
+    }
+
+    /// Accept a comma after the last element of an array or object.
+    fn trailing_commas(&self) -> bool {
+        false
+    }
+
+    /// Accept strings in single quotes, and `\'` as an escape.
+    fn single_quoted_strings(&self) -> bool {
+        false
+    }
+
+    /// Accept object keys without quotes that match
+    /// `[A-Za-z_$][A-Za-z0-9_$]*`.
+    fn unquoted_keys(&self) -> bool {
+        false
+    }
+
+    /// Accept hexadecimal and octal integers, like
+    /// [`Options::allow_radix_integers`].
+    ///
+    /// [`Options::allow_radix_integers`]: crate::Options::allow_radix_integers
+    fn radix_integers(&self) -> bool {
+        false
+    }
+}
+
+/// JSON as specified by RFC 8259. This is the default.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Strict;
+
+impl Dialect for Strict {}
+
+/// JSON with comments and trailing commas, as used by e.g. VS Code settings.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Jsonc;
+
+impl Dialect for Jsonc {
+    fn comments(&self) -> bool {
+        true
+    }
+
+    fn trailing_commas(&self) -> bool {
+        true
+    }
+}
+
+/// The ASCII subset of JSON5.
+///
+/// Numbers with a leading `+` or `.`, `Infinity`, `NaN`, multi-line strings
+/// and non-ASCII whitespace and identifiers are not supported.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Json5;
+
+impl Dialect for Json5 {
+    fn is_whitespace(&self, b: u8) -> bool {
+        matches!(b, b'\t' | b'\n' | b'\x0b' | b'\x0c' | b'\r' | b' ')
+    }
+
+    fn comments(&self) -> bool {
+        true
+    }
+
+    fn trailing_commas(&self) -> bool {
+        true
+    }
+
+    fn single_quoted_strings(&self) -> bool {
+        true
+    }
+
+    fn unquoted_keys(&self) -> bool {
+        true
+    }
+
+    fn radix_integers(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        error::{Error, ErrorKind},
+        options::Options,
+        Value,
+    };
+
+    fn options(dialect: &dyn Dialect) -> Options<'_> {
+        Options {
+            dialect: Some(dialect),
+            ..Options::default()
+        }
+    }
+
+    #[test]
+    fn test_json5() {
+        let json = br#"// An example from json5.org
+{
+  unquoted: 'and you can quote me on that',
+  singleQuotes: 'I can use "double quotes" here',
+  escaped: 'it\'s',
+  hexadecimal: 0xdecaf,
+  trailingComma: 'in objects', andIn: ['arrays',],
+  "backwardsCompatible": "with JSON", /* block
+  comment */
+}
+"#;
+        assert_eq!(
+            Value::from_json_with_options(json, &options(&Json5)),
+            Value::from_json(
+                br#"{
+                    "unquoted": "and you can quote me on that",
+                    "singleQuotes": "I can use \"double quotes\" here",
+                    "escaped": "it's",
+                    "hexadecimal": 912559,
+                    "trailingComma": "in objects",
+                    "andIn": ["arrays"],
+                    "backwardsCompatible": "with JSON"
+                }"#
+            )
+        );
+    }
+
+    #[test]
+    fn test_jsonc() {
+        let options = options(&Jsonc);
+        assert_eq!(
+            Value::from_json_with_options(b"[1, // one\n 2, /* two */ ] // end", &options),
+            Value::from_json(b"[1, 2]")
+        );
+        assert_eq!(
+            Value::from_json_with_options(b"[1] /* end", &options),
+            Err(Error::at(ErrorKind::TrailingData, 4))
+        );
+        assert_eq!(
+            Value::from_json_with_options(b"[1,,]", &options),
+            Err(Error::at(ErrorKind::UnexpectedStartOfValue(b','), 3))
+        );
+        assert_eq!(
+            Value::from_json_with_options(b"['a']", &options),
+            Err(Error::at(ErrorKind::UnexpectedStartOfValue(b'\''), 1))
+        );
+    }
+
+    #[test]
+    fn test_strict() {
+        for options in [Options::default(), options(&Strict)] {
+            for (json, err, offset) in [
+                (&b"[1,]"[..], ErrorKind::UnexpectedStartOfValue(b']'), 3),
+                (br#"{"a": 1,}"#, ErrorKind::ExpectedDoubleQuote(b'}'), 9),
+                (b"{a: 1}", ErrorKind::ExpectedDoubleQuote(b'a'), 2),
+                (b"['a']", ErrorKind::UnexpectedStartOfValue(b'\''), 1),
+                (b"[1] // c", ErrorKind::TrailingData, 4),
+                (b"0x1", ErrorKind::TrailingData, 1),
+            ] {
+                assert_eq!(
+                    Value::from_json_with_options(json, &options),
+                    Err(Error::at(err, offset))
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_custom_dialect() {
+        #[derive(Debug)]
+        struct Semicolons;
+
+        impl Dialect for Semicolons {
+            fn is_whitespace(&self, b: u8) -> bool {
+                b == b';' || Strict.is_whitespace(b)
+            }
+        }
+
+        assert_eq!(
+            Value::from_json_with_options(b";[1 ;; , 2];", &options(&Semicolons)),
+            Value::from_json(b"[1, 2]")
+        );
+
+        // A dialect configured at runtime doesn't need to be `'static`.
+        #[derive(Debug)]
+        struct Configured {
+            comments: bool,
+        }
+
+        impl Dialect for Configured {
+            fn comments(&self) -> bool {
+                self.comments
+            }
+        }
+
+        let dialect = Configured { comments: true };
+        assert_eq!(
+            Value::from_json_with_options(b"[1, /* c */ 2]", &options(&dialect)),
+            Value::from_json(b"[1, 2]")
+        );
+    }
+}
@@ -0,0 +1,147 @@
+use crate::{
+    pointer::{push_index, push_key},
+    Value,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    Modified,
+}
+
+/// A difference between two values at the JSON Pointer `path`, as reported by
+/// [`Value::diff_report`].
+///
+/// `old` is `None` for [`ChangeKind::Added`], and `new` is `None` for
+/// [`ChangeKind::Removed`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Change {
+    pub path: String,
+    pub kind: ChangeKind,
+    pub old: Option<Value>,
+    pub new: Option<Value>,
+}
+
+impl Value {
+    /// Lists the differences between `old` and `new` in document order, for
+    /// showing what changed.
+    ///
+    /// Objects are compared key by key and arrays index by index, so
+    /// inserting an element near the start of an array modifies every
+    /// following element. Any other values that differ, including values of
+    /// different types, are reported as modified as a whole.
+    pub fn diff_report(old: &Value, new: &Value) -> Vec<Change> {
+        let mut changes = Vec::new();
+        let mut stack = vec![(Some(old), Some(new), String::new())];
+        while let Some((old, new, path)) = stack.pop() {
+            let (old, new) = match (old, new) {
+                (Some(old), Some(new)) => (old, new),
+                (old, new) => {
+                    changes.push(Change {
+                        path,
+                        kind: if old.is_some() {
+                            ChangeKind::Removed
+                        } else {
+                            ChangeKind::Added
+                        },
+                        old: old.cloned(),
+                        new: new.cloned(),
+                    });
+                    continue;
+                }
+            };
+            match (old, new) {
+                (Value::Array(old), Value::Array(new)) => {
+                    for i in (0..old.len().max(new.len())).rev() {
+                        let mut path = path.clone();
+                        push_index(&mut path, i);
+                        stack.push((old.get(i), new.get(i), path));
+                    }
+                }
+                (Value::Object(old), Value::Object(new)) => {
+                    let mut keys: Vec<_> =
+                        old.as_inner().keys().chain(new.as_inner().keys()).collect();
+                    keys.sort();
+                    keys.dedup();
+                    for k in keys.into_iter().rev() {
+                        let mut path = path.clone();
+                        push_key(&mut path, k);
+                        stack.push((old.as_inner().get(k), new.as_inner().get(k), path));
+                    }
+                }
+                (old, new) if old != new => changes.push(Change {
+                    path,
+                    kind: ChangeKind::Modified,
+                    old: Some(old.clone()),
+                    new: Some(new.clone()),
+                }),
+                _ => {}
+            }
+        }
+        changes
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn change(path: &str, kind: ChangeKind, old: Option<i32>, new: Option<i32>) -> Change {
+        Change {
+            path: path.into(),
+            kind,
+            old: old.map(Value::from),
+            new: new.map(Value::from),
+        }
+    }
+
+    #[test]
+    fn test_diff_report() {
+        let old = Value::from_json(br#"{"a": 1, "b": {"c": 2, "d": 3}, "e": [1, 2, 3], "g": 5}"#)
+            .unwrap();
+        let new = Value::from_json(
+            br#"{"a": 1, "b": {"c": 4, "x/y": 5}, "e": [1, 3], "f": 6, "g": 5.0}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            Value::diff_report(&old, &new),
+            [
+                change("/b/c", ChangeKind::Modified, Some(2), Some(4)),
+                change("/b/d", ChangeKind::Removed, Some(3), None),
+                change("/b/x~1y", ChangeKind::Added, None, Some(5)),
+                change("/e/1", ChangeKind::Modified, Some(2), Some(3)),
+                change("/e/2", ChangeKind::Removed, Some(3), None),
+                change("/f", ChangeKind::Added, None, Some(6)),
+            ]
+        );
+        assert_eq!(Value::diff_report(&old, &old), []);
+    }
+
+    #[test]
+    fn test_diff_report_types() {
+        let old = Value::from_json(br#"{"a": [1], "b": null}"#).unwrap();
+        let new = Value::from_json(br#"{"a": {"0": 1}, "b": [null]}"#).unwrap();
+        assert_eq!(
+            Value::diff_report(&old, &new),
+            [
+                Change {
+                    path: "/a".into(),
+                    kind: ChangeKind::Modified,
+                    old: Some(old["a"].clone()),
+                    new: Some(new["a"].clone()),
+                },
+                Change {
+                    path: "/b".into(),
+                    kind: ChangeKind::Modified,
+                    old: Some(Value::Null),
+                    new: Some(new["b"].clone()),
+                },
+            ]
+        );
+        assert_eq!(
+            Value::diff_report(&1.into(), &2.into()),
+            [change("", ChangeKind::Modified, Some(1), Some(2))]
+        );
+    }
+}
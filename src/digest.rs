@@ -0,0 +1,62 @@
+use std::fmt::Write;
+
+use sha2::{Digest, Sha256};
+
+use crate::{number::Number, Value};
+
+impl Value {
+    /// Returns the hex encoded SHA-256 digest of the compact serialization,
+    /// where object keys are always sorted and numbers are written in their
+    /// shortest form.
+    ///
+    /// Values that compare equal have the same digest, e.g. `1.0` and `1`,
+    /// `-0.0` and `0`, and `1e19` and `10000000000000000000`, as floats
+    /// without a fractional part are written as integers when they fit in a
+    /// `u64` or `i64`.
+    pub fn sha256_canonical(&self) -> String {
+        let mut value = self.clone();
+        value.map_numbers(|n| {
+            let v = n.as_f64();
+            if !n.is_f64() || v.fract() != 0.0 {
+                return;
+            }
+            if (0.0..18446744073709551616.0).contains(&v) {
+                *n = Number::from(v as u64);
+            } else if (-9223372036854775808.0..0.0).contains(&v) {
+                *n = Number::from(v as i64);
+            }
+        });
+
+        let digest = Sha256::digest(value.to_string());
+        let mut hex = String::with_capacity(2 * digest.len());
+        for b in digest {
+            write!(hex, "{b:02x}").unwrap();
+        }
+        hex
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_sha256_canonical() {
+        let a = Value::from_json(br#"{"a": 1, "b": [true, -0.0, 1e2]}"#).unwrap();
+        let b = Value::from_json(br#"{ "b": [true, 0, 100.0], "a": 1.0 }"#).unwrap();
+        assert_eq!(a, b);
+        assert_eq!(a.sha256_canonical(), b.sha256_canonical());
+        assert_eq!(
+            Value::from_json(b"{}").unwrap().sha256_canonical(),
+            "44136fa355b3678a1146ad16f7e8649e94fb4fc21fe77e8310c060f61caaff8a"
+        );
+
+        let c = Value::from_json(br#"{"a": 1, "b": [true, 0, 101]}"#).unwrap();
+        assert_ne!(a.sha256_canonical(), c.sha256_canonical());
+
+        let ints = Value::from_json(b"[10000000000000000000, -9223372036854775808]").unwrap();
+        let floats = Value::from_json(b"[1e19, -9.223372036854775808e18]").unwrap();
+        assert_eq!(ints, floats);
+        assert_eq!(ints.sha256_canonical(), floats.sha256_canonical());
+    }
+}
@@ -0,0 +1,549 @@
+use std::mem;
+
+use crate::{
+    error::{Error, ErrorKind},
+    object::Object,
+    parser::Reader,
+    pointer::{parse_index, parse_pointer},
+    read_scalar,
+    string::{read_string, JsonString},
+    Value,
+};
+
+/// A JSON document that keeps its comments, whitespace and trailing commas,
+/// so it can be edited and written back without losing them.
+///
+/// Both `//` and `/* */` comments are accepted, as well as a trailing comma
+/// after the last element of an array or object. Values set by an edit are
+/// written compactly.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Document {
+    before: Vec<u8>,
+    root: Node,
+    after: Vec<u8>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Node {
+    Scalar { value: Value, source: Vec<u8> },
+    Array(Container<Node>),
+    Object(Container<Member>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Container<T> {
+    items: Vec<Item<T>>,
+    trailing_comma: bool,
+    /// Trivia before the closing bracket after a trailing comma, or inside
+    /// an empty container.
+    closing: Vec<u8>,
+}
+
+/// An array element or object member with the trivia around it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Item<T> {
+    before: Vec<u8>,
+    inner: T,
+    after: Vec<u8>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Member {
+    key: JsonString,
+    key_source: Vec<u8>,
+    before_colon: Vec<u8>,
+    after_colon: Vec<u8>,
+    value: Node,
+}
+
+impl Document {
+    pub fn parse(bytes: &[u8]) -> Result<Self, Error> {
+        Reader::read_all(bytes, |reader| {
+            let before = read_trivia(reader)?;
+            if reader.at_end() {
+                return Err(ErrorKind::EmptyInput);
+            }
+            let root = read_node(reader)?;
+            let after = read_trivia(reader)?;
+            Ok(Self {
+                before,
+                root,
+                after,
+            })
+        })
+    }
+
+    /// Returns the value of the document, without the trivia.
+    ///
+    /// For duplicate object keys the last one wins, like
+    /// [`Value::from_json`].
+    pub fn value(&self) -> Value {
+        self.root.to_value()
+    }
+
+    /// Sets the value at the JSON Pointer `ptr`.
+    ///
+    /// A missing object key is added after the last member, and `-` appends
+    /// to an array, with the same indentation as the preceding item. Returns
+    /// `false`, leaving the document unchanged, if there is nowhere to put
+    /// the value.
+    pub fn set(&mut self, ptr: &str, value: Value) -> bool {
+        let Some(mut tokens) = parse_pointer(ptr) else {
+            return false;
+        };
+        let Some(last) = tokens.pop() else {
+            self.root = value.into();
+            return true;
+        };
+        let Some(parent) = self.root.get_mut(&tokens) else {
+            return false;
+        };
+        match parent {
+            Node::Object(obj) => match obj.find_mut(&last) {
+                Some(member) => member.value = value.into(),
+                None => {
+                    let key = JsonString::from(last);
+                    let after_colon = obj
+                        .items
+                        .last()
+                        .map(|item| item.inner.after_colon.clone())
+                        .unwrap_or_default();
+                    obj.push(Member {
+                        key_source: key.to_string().into_bytes(),
+                        key,
+                        before_colon: Vec::new(),
+                        after_colon,
+                        value: value.into(),
+                    });
+                }
+            },
+            Node::Array(arr) if last == "-" => arr.push(value.into()),
+            Node::Array(arr) => match parse_index(&last).and_then(|i| arr.items.get_mut(i)) {
+                Some(item) => item.inner = value.into(),
+                None => return false,
+            },
+            Node::Scalar { .. } => return false,
+        }
+        true
+    }
+
+    /// Removes and returns the value at the JSON Pointer `ptr`, together
+    /// with the comments before it.
+    ///
+    /// The root (`""`) can't be removed, so it's a no-op returning `None`.
+    pub fn remove(&mut self, ptr: &str) -> Option<Value> {
+        let mut tokens = parse_pointer(ptr)?;
+        let last = tokens.pop()?;
+        match self.root.get_mut(&tokens)? {
+            Node::Object(obj) => {
+                let index = obj
+                    .items
+                    .iter()
+                    .rposition(|item| item.inner.key.as_str() == Some(last.as_str()))?;
+                Some(obj.remove(index).value.to_value())
+            }
+            Node::Array(arr) => {
+                let index = parse_index(&last).filter(|&i| i < arr.items.len())?;
+                Some(arr.remove(index).to_value())
+            }
+            Node::Scalar { .. } => None,
+        }
+    }
+
+    /// Serializes the document, with its trivia.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = self.before.clone();
+        self.root.write(&mut out);
+        out.extend_from_slice(&self.after);
+        out
+    }
+}
+
+impl Node {
+    fn get_mut(&mut self, tokens: &[String]) -> Option<&mut Node> {
+        let mut node = self;
+        for token in tokens {
+            node = match node {
+                Node::Object(obj) => &mut obj.find_mut(token)?.value,
+                Node::Array(arr) => &mut arr.items.get_mut(parse_index(token)?)?.inner,
+                Node::Scalar { .. } => return None,
+            };
+        }
+        Some(node)
+    }
+
+    fn to_value(&self) -> Value {
+        match self {
+            Node::Scalar { value, .. } => value.clone(),
+            Node::Array(arr) => arr.items.iter().map(|item| item.inner.to_value()).collect(),
+            Node::Object(obj) => {
+                let mut res = Object::new();
+                for item in &obj.items {
+                    res.insert(item.inner.key.clone(), item.inner.value.to_value());
+                }
+                Value::Object(res)
+            }
+        }
+    }
+
+    fn write(&self, out: &mut Vec<u8>) {
+        match self {
+            Node::Scalar { source, .. } => out.extend_from_slice(source),
+            Node::Array(arr) => arr.write(out, b'[', b']', |node, out| node.write(out)),
+            Node::Object(obj) => obj.write(out, b'{', b'}', |member, out| {
+                out.extend_from_slice(&member.key_source);
+                out.extend_from_slice(&member.before_colon);
+                out.push(b':');
+                out.extend_from_slice(&member.after_colon);
+                member.value.write(out);
+            }),
+        }
+    }
+}
+
+impl From<Value> for Node {
+    fn from(value: Value) -> Self {
+        match value {
+            Value::Array(arr) => Node::Array(Container::compact(arr.into_iter().map(Node::from))),
+            Value::Object(obj) => {
+                Node::Object(Container::compact(obj.into_iter().map(|(key, value)| {
+                    Member {
+                        key_source: key.to_string().into_bytes(),
+                        key,
+                        before_colon: Vec::new(),
+                        after_colon: Vec::new(),
+                        value: value.into(),
+                    }
+                })))
+            }
+            value => Node::Scalar {
+                source: value.to_string().into_bytes(),
+                value,
+            },
+        }
+    }
+}
+
+impl<T> Container<T> {
+    /// Creates a container without any trivia.
+    fn compact(inners: impl IntoIterator<Item = T>) -> Self {
+        Self {
+            items: inners
+                .into_iter()
+                .map(|inner| Item {
+                    before: Vec::new(),
+                    inner,
+                    after: Vec::new(),
+                })
+                .collect(),
+            trailing_comma: false,
+            closing: Vec::new(),
+        }
+    }
+
+    /// Appends `inner`, indented like the current last item.
+    fn push(&mut self, inner: T) {
+        let (before, after) = match self.items.last_mut() {
+            None => (Vec::new(), Vec::new()),
+            Some(last) => {
+                let indent = last.before.len() - whitespace_len(last.before.iter().rev());
+                let after = if self.trailing_comma {
+                    Vec::new()
+                } else {
+                    // Keep the trivia before the closing bracket at the end.
+                    mem::take(&mut last.after)
+                };
+                (last.before[indent..].to_vec(), after)
+            }
+        };
+        self.items.push(Item {
+            before,
+            inner,
+            after,
+        });
+    }
+
+    /// Removes the item at `index`. Trivia ending the previous line, such
+    /// as a trailing `//` comment, is kept.
+    fn remove(&mut self, index: usize) -> T {
+        let item = self.items.remove(index);
+        let line_end = &item.before[..line_end_len(&item.before)];
+        let target = match self.items.get_mut(index) {
+            Some(next) => &mut next.before,
+            None if self.trailing_comma => &mut self.closing,
+            None => {
+                if let Some(last) = self.items.last_mut() {
+                    last.after = [line_end.trim_ascii_end(), &item.after].concat();
+                }
+                return item.inner;
+            }
+        };
+        let target_end = line_end_len(target);
+        target.splice(..target_end, line_end.iter().copied());
+        item.inner
+    }
+
+    fn write(&self, out: &mut Vec<u8>, open: u8, close: u8, f: impl Fn(&T, &mut Vec<u8>)) {
+        out.push(open);
+        for (i, item) in self.items.iter().enumerate() {
+            if i != 0 {
+                out.push(b',');
+            }
+            out.extend_from_slice(&item.before);
+            f(&item.inner, out);
+            out.extend_from_slice(&item.after);
+        }
+        if self.trailing_comma && !self.items.is_empty() {
+            out.push(b',');
+        }
+        out.extend_from_slice(&self.closing);
+        out.push(close);
+    }
+}
+
+impl Container<Member> {
+    /// Finds the member with `key`, the last one if there are duplicates.
+    fn find_mut(&mut self, key: &str) -> Option<&mut Member> {
+        self.items
+            .iter_mut()
+            .rev()
+            .map(|item| &mut item.inner)
+            .find(|member| member.key.as_str() == Some(key))
+    }
+}
+
+fn whitespace_len<'a>(bytes: impl IntoIterator<Item = &'a u8>) -> usize {
+    bytes
+        .into_iter()
+        .take_while(|b| matches!(b, b'\t' | b'\n' | b'\r' | b' '))
+        .count()
+}
+
+/// Length of the start of `trivia` that ends the previous line: spaces, an
+/// optional `//` comment and a newline.
+fn line_end_len(trivia: &[u8]) -> usize {
+    let mut len = trivia
+        .iter()
+        .take_while(|&&b| b == b' ' || b == b'\t')
+        .count();
+    if trivia[len..].starts_with(b"//") {
+        len += trivia[len..]
+            .iter()
+            .position(|&b| b == b'\n')
+            .unwrap_or(trivia.len() - len);
+    } else if trivia[len..].starts_with(b"\r\n") {
+        len += 1;
+    }
+    if trivia[len..].starts_with(b"\n") {
+        len += 1;
+    }
+    len
+}
+
+/// Reads whitespace and comments.
+fn read_trivia(reader: &mut Reader) -> Result<Vec<u8>, ErrorKind> {
+    let (trivia, ()) = reader.parse_slice(|reader| loop {
+        match reader.remaining() {
+            [b, ..] if reader.is_whitespace(*b) => {
+                reader.read_byte()?;
+            }
+            [b'/', b'/', ..] => {
+                while let Some(b) = reader.peek_byte() {
+                    reader.read_byte()?;
+                    if b == b'\n' {
+                        break;
+                    }
+                }
+            }
+            [b'/', b'*', ..] => {
+                reader.read_bytes::<2>()?;
+                while !reader.remaining().starts_with(b"*/") {
+                    reader.read_byte()?;
+                }
+                reader.read_bytes::<2>()?;
+            }
+            _ => return Ok(()),
+        }
+    })?;
+    Ok(trivia.to_vec())
+}
+
+fn read_node(reader: &mut Reader) -> Result<Node, ErrorKind> {
+    let Some(b) = reader.peek_byte() else {
+        return Err(ErrorKind::UnexpectedEof);
+    };
+    Ok(match b {
+        b'[' => {
+            reader.read_byte()?;
+            Node::Array(read_items(reader, b']', read_node)?)
+        }
+        b'{' => {
+            reader.read_byte()?;
+            Node::Object(read_items(reader, b'}', |reader| {
+                let (key_source, key) = reader.parse_slice(read_string)?;
+                let before_colon = read_trivia(reader)?;
+                match reader.read_byte()? {
+                    b':' => {}
+                    b => return Err(ErrorKind::ExpectedColon(b)),
+                }
+                let after_colon = read_trivia(reader)?;
+                let value = read_node(reader)?;
+                Ok(Member {
+                    key,
+                    key_source: key_source.to_vec(),
+                    before_colon,
+                    after_colon,
+                    value,
+                })
+            })?)
+        }
+        _ => {
+            let (source, value) = reader.parse_slice(|reader| read_scalar(reader, b))?;
+            Node::Scalar {
+                value,
+                source: source.to_vec(),
+            }
+        }
+    })
+}
+
+/// Reads the items of a container after its opening bracket, up to and
+/// including `close`.
+fn read_items<T>(
+    reader: &mut Reader,
+    close: u8,
+    mut read_item: impl FnMut(&mut Reader) -> Result<T, ErrorKind>,
+) -> Result<Container<T>, ErrorKind> {
+    let mut items = Vec::new();
+    loop {
+        let before = read_trivia(reader)?;
+        if reader.peek_byte() == Some(close) {
+            reader.read_byte()?;
+            return Ok(Container {
+                trailing_comma: !items.is_empty(),
+                items,
+                closing: before,
+            });
+        }
+        let inner = read_item(reader)?;
+        let after = read_trivia(reader)?;
+        items.push(Item {
+            before,
+            inner,
+            after,
+        });
+        match reader.read_byte()? {
+            b',' => {}
+            b if b == close => {
+                return Ok(Container {
+                    items,
+                    trailing_comma: false,
+                    closing: Vec::new(),
+                })
+            }
+            b if close == b']' => return Err(ErrorKind::ExpectedCommaOrRightBracket(b)),
+            b => return Err(ErrorKind::ExpectedCommaOrRightBrace(b)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const CONFIG: &[u8] = br#"// Settings
+{
+    "name": "demo", // inline
+    /* the port */
+    "port": 8080,
+    "tags": ["a", "b",],
+}
+"#;
+
+    #[test]
+    fn test_round_trip() {
+        let doc = Document::parse(CONFIG).unwrap();
+        assert_eq!(doc.to_bytes(), CONFIG);
+        assert_eq!(
+            doc.value(),
+            Value::from_json(br#"{"name": "demo", "port": 8080, "tags": ["a", "b"]}"#).unwrap()
+        );
+
+        let bytes = b" [ 1.50 , \"\\u0061\" ,{ } ,[/**/] ] ";
+        assert_eq!(Document::parse(bytes).unwrap().to_bytes(), bytes);
+    }
+
+    #[test]
+    fn test_edit() {
+        let mut doc = Document::parse(CONFIG).unwrap();
+        assert!(doc.set("/port", 9090.into()));
+        assert!(doc.set("/tags/-", "c".into()));
+        assert!(doc.set("/debug", Value::from_json(br#"{"level":1}"#).unwrap()));
+        assert!(!doc.set("/missing/key", Value::Null));
+        assert!(!doc.set("/tags/5", Value::Null));
+        assert_eq!(
+            String::from_utf8(doc.to_bytes()).unwrap(),
+            r#"// Settings
+{
+    "name": "demo", // inline
+    /* the port */
+    "port": 9090,
+    "tags": ["a", "b", "c",],
+    "debug": {"level":1},
+}
+"#
+        );
+
+        assert_eq!(doc.remove("/port"), Some(9090.into()));
+        assert_eq!(doc.remove("/tags/0"), Some("a".into()));
+        assert_eq!(doc.remove("/port"), None);
+        assert_eq!(doc.remove(""), None);
+        assert_eq!(
+            String::from_utf8(doc.to_bytes()).unwrap(),
+            r#"// Settings
+{
+    "name": "demo", // inline
+    "tags": ["b", "c",],
+    "debug": {"level":1},
+}
+"#
+        );
+    }
+
+    #[test]
+    fn test_edit_without_trailing_comma() {
+        let mut doc = Document::parse(b"{\n  \"a\": 1\n}").unwrap();
+        assert!(doc.set("/b", 2.into()));
+        assert_eq!(doc.to_bytes(), b"{\n  \"a\": 1,\n  \"b\": 2\n}");
+        assert_eq!(doc.remove("/b"), Some(2.into()));
+        assert_eq!(doc.to_bytes(), b"{\n  \"a\": 1\n}");
+
+        let mut doc = Document::parse(b"[1, // one\n 2\n]").unwrap();
+        assert_eq!(doc.remove("/1"), Some(2.into()));
+        assert_eq!(doc.to_bytes(), b"[1 // one\n]");
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(
+            Document::parse(b" // only\n"),
+            Err(Error::at(ErrorKind::EmptyInput, 9))
+        );
+        assert_eq!(
+            Document::parse(b"[1 /* 2 ]"),
+            Err(Error::at(ErrorKind::UnexpectedEof, 9))
+        );
+        assert_eq!(
+            Document::parse(b"[,]"),
+            Err(Error::at(ErrorKind::UnexpectedStartOfValue(b','), 1))
+        );
+        assert_eq!(
+            Document::parse(b"{\"a\" 1}"),
+            Err(Error::at(ErrorKind::ExpectedColon(b'1'), 6))
+        );
+        assert_eq!(
+            Document::parse(b"[1] /"),
+            Err(Error::at(ErrorKind::TrailingData, 4))
+        );
+    }
+}
@@ -1,5 +1,10 @@
+use crate::string::JsonString;
+
+/// What went wrong while parsing.
 #[derive(Debug, PartialEq, Eq)]
-pub enum Error {
+pub enum ErrorKind {
+    /// The input is empty or only whitespace.
+    EmptyInput,
     UnexpectedEof,
     TrailingData,
     InvalidControlCharacter(u8),
@@ -7,17 +12,198 @@ pub enum Error {
     UnexpectedEscape(u8),
     InvalidHexChar(u8),
     InvalidUtf8Char,
+    /// A string contains an unpaired surrogate escape, with
+    /// [`Options::reject_lone_surrogates`].
+    ///
+    /// [`Options::reject_lone_surrogates`]: crate::Options::reject_lone_surrogates
+    LoneSurrogate,
     UnexpectedStartOfValue(u8),
     ExpectedNull,
     ExpectedTrue,
     ExpectedFalse,
     InvalidDigit(u8),
     InfiniteFloat,
+    /// An integer doesn't fit in a `u64` or `i64`, with
+    /// [`OverflowPolicy::Error`].
+    ///
+    /// [`OverflowPolicy::Error`]: crate::OverflowPolicy::Error
+    IntegerOverflow,
 
     ExpectedLeftBracket(u8),
     ExpectedCommaOrRightBracket(u8),
+    /// An element of an array read with [`Array::from_json_as_f64`] or
+    /// [`Array::from_json_as_i64`] starts with this byte instead of a number.
+    ///
+    /// [`Array::from_json_as_f64`]: crate::Array::from_json_as_f64
+    /// [`Array::from_json_as_i64`]: crate::Array::from_json_as_i64
+    ExpectedNumber(u8),
+    /// An element of an array read with [`Array::from_json_as_i64`] is not
+    /// an integer that fits in an `i64`.
+    ///
+    /// [`Array::from_json_as_i64`]: crate::Array::from_json_as_i64
+    ExpectedI64,
 
     ExpectedLeftBrace(u8),
     ExpectedColon(u8),
     ExpectedCommaOrRightBrace(u8),
+    ForbiddenKey(JsonString),
+    /// An object key is longer than [`Options::max_key_len`].
+    ///
+    /// [`Options::max_key_len`]: crate::Options::max_key_len
+    KeyTooLong,
+
+    /// A string has more escapes than [`Options::max_escapes_per_string`].
+    ///
+    /// [`Options::max_escapes_per_string`]: crate::Options::max_escapes_per_string
+    TooManyEscapes,
+    /// The parsed value takes up more than [`Options::max_alloc_bytes`].
+    ///
+    /// [`Options::max_alloc_bytes`]: crate::Options::max_alloc_bytes
+    AllocationLimitExceeded,
+}
+
+impl ErrorKind {
+    /// Returns whether the input ended in the middle of a value.
+    pub fn is_eof(&self) -> bool {
+        matches!(self, ErrorKind::UnexpectedEof)
+    }
+
+    /// Returns whether appending more bytes to the input could make it
+    /// parse, i.e. the input is a prefix of a valid document.
+    ///
+    /// A multi-byte UTF-8 character cut off at the end of the input is
+    /// reported as [`ErrorKind::InvalidUtf8Char`], which is not counted here.
+    pub fn is_recoverable_with_more_input(&self) -> bool {
+        matches!(self, ErrorKind::EmptyInput | ErrorKind::UnexpectedEof)
+    }
+
+    /// Returns whether the error is about how values are arranged, e.g. a
+    /// missing comma, rather than about the contents of a single token.
+    pub fn is_structural(&self) -> bool {
+        match self {
+            ErrorKind::TrailingData
+            | ErrorKind::ExpectedDoubleQuote(_)
+            | ErrorKind::UnexpectedStartOfValue(_)
+            | ErrorKind::ExpectedLeftBracket(_)
+            | ErrorKind::ExpectedCommaOrRightBracket(_)
+            | ErrorKind::ExpectedLeftBrace(_)
+            | ErrorKind::ExpectedColon(_)
+            | ErrorKind::ExpectedCommaOrRightBrace(_) => true,
+            ErrorKind::EmptyInput
+            | ErrorKind::UnexpectedEof
+            | ErrorKind::InvalidControlCharacter(_)
+            | ErrorKind::UnexpectedEscape(_)
+            | ErrorKind::InvalidHexChar(_)
+            | ErrorKind::InvalidUtf8Char
+            | ErrorKind::LoneSurrogate
+            | ErrorKind::ExpectedNull
+            | ErrorKind::ExpectedTrue
+            | ErrorKind::ExpectedFalse
+            | ErrorKind::InvalidDigit(_)
+            | ErrorKind::InfiniteFloat
+            | ErrorKind::IntegerOverflow
+            | ErrorKind::ExpectedNumber(_)
+            | ErrorKind::ExpectedI64
+            | ErrorKind::ForbiddenKey(_)
+            | ErrorKind::KeyTooLong
+            | ErrorKind::TooManyEscapes
+            | ErrorKind::AllocationLimitExceeded => false,
+        }
+    }
+}
+
+/// An error from parsing JSON, with the byte offset where parsing stopped.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Error {
+    kind: ErrorKind,
+    offset: Option<usize>,
+}
+
+impl Error {
+    pub(crate) fn at(kind: ErrorKind, offset: usize) -> Self {
+        Self {
+            kind,
+            offset: Some(offset),
+        }
+    }
+
+    pub fn kind(&self) -> &ErrorKind {
+        &self.kind
+    }
+
+    pub fn into_kind(self) -> ErrorKind {
+        self.kind
+    }
+
+    /// The byte offset into the input where parsing stopped, if known.
+    pub fn offset(&self) -> Option<usize> {
+        self.offset
+    }
+
+    /// See [`ErrorKind::is_eof`].
+    pub fn is_eof(&self) -> bool {
+        self.kind.is_eof()
+    }
+
+    /// See [`ErrorKind::is_recoverable_with_more_input`].
+    pub fn is_recoverable_with_more_input(&self) -> bool {
+        self.kind.is_recoverable_with_more_input()
+    }
+
+    /// See [`ErrorKind::is_structural`].
+    pub fn is_structural(&self) -> bool {
+        self.kind.is_structural()
+    }
+}
+
+impl From<ErrorKind> for Error {
+    fn from(kind: ErrorKind) -> Self {
+        Self { kind, offset: None }
+    }
+}
+
+/// A problem in the input that doesn't prevent parsing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Warning {
+    /// The number starting at byte `offset` can't be represented exactly, so
+    /// it no longer reads as `original` when serialized.
+    PrecisionLoss { offset: usize, original: String },
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{Array, Value};
+
+    #[test]
+    fn test_classification() {
+        let classify = |json: &[u8]| {
+            let e = Value::from_json(json).unwrap_err();
+            (
+                e.is_eof(),
+                e.is_recoverable_with_more_input(),
+                e.is_structural(),
+            )
+        };
+        assert_eq!(classify(br#"{"a": [1, "#), (true, true, false));
+        assert_eq!(classify(b"  "), (false, true, false));
+        assert_eq!(classify(b"[1 2]"), (false, false, true));
+        assert_eq!(classify(b"{1: 2}"), (false, false, true));
+        assert_eq!(classify(b"1 2"), (false, false, true));
+        assert_eq!(classify(b"[nul]"), (false, false, false));
+        assert_eq!(classify(br#""\x""#), (false, false, false));
+    }
+
+    #[test]
+    fn test_offset() {
+        let e = Value::from_json(b"[1, x]").unwrap_err();
+        assert_eq!(e.kind(), &ErrorKind::UnexpectedStartOfValue(b'x'));
+        assert_eq!(e.offset(), Some(4));
+        assert_eq!(e, Error::at(ErrorKind::UnexpectedStartOfValue(b'x'), 4));
+        assert_ne!(e, ErrorKind::UnexpectedStartOfValue(b'x').into());
+        assert_eq!(Value::from_json(b"1 2").unwrap_err().offset(), Some(2));
+        assert_eq!(Value::from_json(b"[1, ").unwrap_err().offset(), Some(4));
+        assert_eq!(Array::from_json(b"{}").unwrap_err().offset(), Some(1));
+        assert_eq!(Error::from(ErrorKind::TrailingData).offset(), None);
+    }
 }
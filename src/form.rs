@@ -0,0 +1,91 @@
+use std::fmt::Write;
+
+use crate::{object::Object, string::JsonString, Value};
+
+/// Error returned by [`Object::to_form_urlencoded`].
+#[derive(Debug, PartialEq, Eq)]
+pub enum FormError {
+    /// The value of this key is an array or object.
+    NestedValue(JsonString),
+    /// This key or string value contains a lone surrogate.
+    InvalidUnicode(JsonString),
+}
+
+/// Percent-encodes `s` as `application/x-www-form-urlencoded`.
+fn encode(out: &mut String, s: &JsonString) -> Result<(), FormError> {
+    let s = s
+        .as_str()
+        .ok_or_else(|| FormError::InvalidUnicode(s.clone()))?;
+    for &b in s.as_bytes() {
+        match b {
+            b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'*' | b'-' | b'.' | b'_' => {
+                out.push(b.into())
+            }
+            b' ' => out.push('+'),
+            _ => write!(out, "%{b:02X}").unwrap(),
+        }
+    }
+    Ok(())
+}
+
+impl Object {
+    /// Encodes a flat object as `key=value&key2=value2`.
+    ///
+    /// Strings are written without quotes, `null` as an empty value and
+    /// other scalars as their JSON text. Arrays and objects are not flattened
+    /// and result in [`FormError::NestedValue`].
+    pub fn to_form_urlencoded(&self) -> Result<String, FormError> {
+        let mut out = String::new();
+        for (i, (k, v)) in self.as_inner().iter().enumerate() {
+            if i != 0 {
+                out.push('&');
+            }
+            encode(&mut out, k)?;
+            out.push('=');
+            match v {
+                Value::Null => {}
+                Value::Bool(v) => write!(out, "{v}").unwrap(),
+                Value::Number(v) => write!(out, "{v}").unwrap(),
+                Value::String(v) => encode(&mut out, v)?,
+                Value::Array(_) | Value::Object(_) => {
+                    return Err(FormError::NestedValue(k.clone()));
+                }
+            }
+        }
+        Ok(out)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_to_form_urlencoded() {
+        let obj = Object::from_json(
+            r#"{"name": "Jørn & co", "age": 42, "x y": null, "ok": true}"#.as_bytes(),
+        )
+        .unwrap();
+        assert_eq!(
+            obj.to_form_urlencoded(),
+            Ok("age=42&name=J%C3%B8rn+%26+co&ok=true&x+y=".to_owned())
+        );
+    }
+
+    #[test]
+    fn test_to_form_urlencoded_errors() {
+        let obj = Object::from_json(br#"{"a": 1, "b": [1]}"#).unwrap();
+        assert_eq!(
+            obj.to_form_urlencoded(),
+            Err(FormError::NestedValue("b".into()))
+        );
+
+        let obj = Object::from_json(br#"{"a": "\ud800"}"#).unwrap();
+        assert_eq!(
+            obj.to_form_urlencoded(),
+            Err(FormError::InvalidUnicode(
+                JsonString::from_ill_formed_utf16(&[0xd800])
+            ))
+        );
+    }
+}
@@ -1,22 +1,70 @@
 mod array;
+#[cfg(feature = "serde")]
+mod de;
+mod dialect;
+mod diff;
+#[cfg(feature = "sha2")]
+mod digest;
+mod document;
 mod error;
+mod form;
 mod number;
 mod object;
+mod options;
 mod parser;
+mod pointer;
+mod pool;
+mod probe;
+mod schema;
+mod ser;
+mod spanned;
 mod string;
+#[cfg(feature = "testing")]
+mod testing;
 
-use std::{collections::BTreeMap, fmt::Display};
+use std::{
+    collections::BTreeMap,
+    fmt::{Debug, Display},
+    io::{self, BufWriter, Write},
+    ops::{Index, Range},
+};
 
+#[cfg(feature = "serde_json")]
+use crate::pointer::{push_index, push_key, push_token};
+#[cfg(feature = "testing")]
+pub use crate::testing::{assert_round_trips, RoundTripError};
+pub use crate::{
+    array::Array,
+    dialect::{Dialect, Json5, Jsonc, Strict},
+    diff::{Change, ChangeKind},
+    document::Document,
+    error::{Error, ErrorKind, Warning},
+    form::FormError,
+    number::Number,
+    object::{Entry, Object, OccupiedEntry, Presence, VacantEntry, WrongType},
+    options::{NumberParser, Options, OverflowPolicy},
+    parser::Parser,
+    pool::{SharedJsonString, SharedStringPool, SharedValue, StringPool},
+    probe::Shape,
+    schema::{Schema, SchemaError, SchemaErrorKind},
+    ser::{ArrayWriter, IntegralStyle, NonFinitePolicy, WriteOptions},
+    spanned::{Spanned, SpannedValue},
+    string::{escape, unescape, JsonStr, JsonString},
+};
 use crate::{
-    array::{read_array, Array},
-    error::Error,
-    number::{read_number, Number},
-    object::{read_object, Object},
+    array::{read_array, skip_array},
+    number::read_number,
+    object::{read_object, skip_object},
     parser::Reader,
-    string::{read_string, JsonString},
+    spanned::read_spanned,
+    string::{read_bare_word, read_string, skip_string},
 };
 
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+/// A JSON value.
+///
+/// The `Debug` output tags scalars with their type, and writes containers
+/// and strings like JSON, e.g. `{"a": [Number(1), String("x")]}`.
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum Value {
     Null,
     Bool(bool),
@@ -38,6 +86,20 @@ impl From<Number> for Value {
     }
 }
 
+macro_rules! impl_from_int_for_value {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Value {
+                fn from(value: $t) -> Self {
+                    Self::Number(value.into())
+                }
+            }
+        )*
+    };
+}
+
+impl_from_int_for_value!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+
 impl TryFrom<f64> for Value {
     type Error = ();
 
@@ -88,49 +150,601 @@ impl From<BTreeMap<JsonString, Value>> for Value {
     }
 }
 
-fn read_value(reader: &mut Reader) -> Result<Value, Error> {
+impl<T: Into<Value>> From<Option<T>> for Value {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(Value::Null, Into::into)
+    }
+}
+
+impl FromIterator<Value> for Value {
+    fn from_iter<T: IntoIterator<Item = Value>>(iter: T) -> Self {
+        Self::Array(iter.into_iter().collect())
+    }
+}
+
+impl FromIterator<(JsonString, Value)> for Value {
+    fn from_iter<T: IntoIterator<Item = (JsonString, Value)>>(iter: T) -> Self {
+        Self::Object(iter.into_iter().collect())
+    }
+}
+
+pub(crate) fn read_value(reader: &mut Reader) -> Result<Value, ErrorKind> {
     reader.skip_whitespace();
 
     let Some(b) = reader.peek_byte() else {
-        return Err(Error::UnexpectedEof);
+        return Err(ErrorKind::UnexpectedEof);
     };
 
     let v = match b {
-        b'n' => {
-            if reader.read_bytes::<4>()? != b"null" {
-                return Err(Error::ExpectedNull);
-            }
-            Value::Null
+        b'[' => read_array(reader).map(Value::Array),
+        b'{' => read_object(reader).map(Value::Object),
+        _ => read_leaf(reader, b),
+    };
+    #[cfg(feature = "tracing")]
+    if let Err(e) = &v {
+        tracing::trace!(offset = reader.offset(), error = ?e, "failed to read value");
+    }
+    let v = v?;
+
+    reader.skip_whitespace();
+    Ok(v)
+}
+
+/// Reads a scalar with [`read_scalar`] and applies
+/// [`Options::value_transform`] to it.
+pub(crate) fn read_leaf(reader: &mut Reader, b: u8) -> Result<Value, ErrorKind> {
+    let v = read_scalar(reader, b)?;
+    Ok(match reader.options().value_transform {
+        Some(f) => f(v),
+        None => v,
+    })
+}
+
+/// Reads a top-level value, reporting input without any value as
+/// [`ErrorKind::EmptyInput`].
+fn read_document(reader: &mut Reader) -> Result<Value, ErrorKind> {
+    reader.skip_whitespace();
+    if reader.at_end() {
+        return Err(ErrorKind::EmptyInput);
+    }
+    read_value(reader)
+}
+
+/// Validates a value like [`read_value`] without building it.
+pub(crate) fn skip_value(reader: &mut Reader) -> Result<(), ErrorKind> {
+    reader.skip_whitespace();
+
+    let Some(b) = reader.peek_byte() else {
+        return Err(ErrorKind::UnexpectedEof);
+    };
+
+    match b {
+        b'[' => {
+            skip_array(reader)?;
         }
-        b'f' => {
-            if reader.read_bytes::<5>()? != b"false" {
-                return Err(Error::ExpectedFalse);
-            }
-            Value::Bool(false)
+        b'{' => {
+            skip_object(reader)?;
         }
-        b't' => {
-            if reader.read_bytes::<4>()? != b"true" {
-                return Err(Error::ExpectedTrue);
-            }
-            Value::Bool(true)
+        b'"' => skip_string(reader)?,
+        // Numbers and literals are parsed without allocating anyway.
+        _ => {
+            read_scalar(reader, b)?;
         }
-        b'-' | b'0'..=b'9' => Value::Number(read_number(reader)?),
-        b'"' => Value::String(read_string(reader)?),
-        b'[' => Value::Array(read_array(reader)?),
-        b'{' => Value::Object(read_object(reader)?),
-        _ => return Err(Error::UnexpectedStartOfValue(b)),
-    };
+    }
 
     reader.skip_whitespace();
-    Ok(v)
+    Ok(())
+}
+
+pub(crate) fn read_scalar(reader: &mut Reader, b: u8) -> Result<Value, ErrorKind> {
+    let case_insensitive = reader.options().case_insensitive_literals;
+    let is_literal = |bytes: &[u8], literal: &[u8]| {
+        if case_insensitive {
+            bytes.eq_ignore_ascii_case(literal)
+        } else {
+            bytes == literal
+        }
+    };
+
+    if reader.options().allow_unquoted_values && (b.is_ascii_alphabetic() || b == b'_' || b == b'$')
+    {
+        let word = read_bare_word(reader)?;
+        return Ok(match word.as_str().map(str::as_bytes) {
+            Some(w) if is_literal(w, b"null") => Value::Null,
+            Some(w) if is_literal(w, b"true") => Value::Bool(true),
+            Some(w) if is_literal(w, b"false") => Value::Bool(false),
+            _ => Value::String(word),
+        });
+    }
+
+    Ok(
+        match if case_insensitive {
+            b.to_ascii_lowercase()
+        } else {
+            b
+        } {
+            b'n' => {
+                if !is_literal(reader.read_bytes::<4>()?, b"null") {
+                    return Err(ErrorKind::ExpectedNull);
+                }
+                Value::Null
+            }
+            b'f' => {
+                if !is_literal(reader.read_bytes::<5>()?, b"false") {
+                    return Err(ErrorKind::ExpectedFalse);
+                }
+                Value::Bool(false)
+            }
+            b't' => {
+                if !is_literal(reader.read_bytes::<4>()?, b"true") {
+                    return Err(ErrorKind::ExpectedTrue);
+                }
+                Value::Bool(true)
+            }
+            b'-' | b'0'..=b'9' => Value::Number(read_number(reader)?),
+            b'"' => Value::String(read_string(reader)?),
+            b'\'' if reader.dialect().single_quoted_strings() => {
+                Value::String(read_string(reader)?)
+            }
+            _ => return Err(ErrorKind::UnexpectedStartOfValue(b)),
+        },
+    )
 }
 
 impl Value {
+    pub fn null() -> Self {
+        Value::Null
+    }
+
+    pub fn bool(b: bool) -> Self {
+        Value::Bool(b)
+    }
+
+    pub fn string(s: impl Into<JsonString>) -> Self {
+        Value::String(s.into())
+    }
+
+    /// Creates an empty array.
+    pub fn array() -> Self {
+        Value::Array(Array::new())
+    }
+
+    /// Creates an empty object.
+    pub fn object() -> Self {
+        Value::Object(Object::new())
+    }
+
     pub fn from_json(bytes: &[u8]) -> Result<Self, Error> {
-        Reader::read_all(bytes, read_value)
+        Reader::read_all(bytes, read_document)
+    }
+
+    pub fn from_json_with_options(bytes: &[u8], options: &Options) -> Result<Self, Error> {
+        Reader::read_all_with_options(bytes, options, read_document)
+    }
+
+    /// Like [`Value::from_json`], but also reports numbers that lost
+    /// precision while parsing.
+    pub fn from_json_checked(bytes: &[u8]) -> Result<(Self, Vec<Warning>), Error> {
+        Reader::read_all(bytes, |reader| {
+            reader.warnings = Some(Vec::new());
+            let value = read_document(reader)?;
+            Ok((value, reader.warnings.take().unwrap()))
+        })
+    }
+
+    /// Estimates the length in bytes of the serialized value, for pre-sizing
+    /// output buffers.
+    ///
+    /// The estimate is at least the length of the compact serialization.
+    pub fn serialized_len_estimate(&self) -> usize {
+        match self {
+            Value::Null => 4,
+            Value::Bool(_) => 5,
+            Value::Number(v) => v.serialized_len(),
+            Value::String(v) => v.serialized_len(),
+            Value::Array(arr) => {
+                2 + arr.len().saturating_sub(1)
+                    + arr
+                        .iter()
+                        .map(Value::serialized_len_estimate)
+                        .sum::<usize>()
+            }
+            Value::Object(obj) => {
+                2 + obj.len().saturating_sub(1)
+                    + obj
+                        .as_inner()
+                        .iter()
+                        .map(|(k, v)| k.serialized_len() + 1 + v.serialized_len_estimate())
+                        .sum::<usize>()
+            }
+        }
+    }
+
+    /// Serializes the value into a buffer pre-sized with
+    /// [`Value::serialized_len_estimate`].
+    ///
+    /// Fails on numbers that are not finite, as described for
+    /// [`WriteOptions::non_finite`].
+    pub fn to_bytes(&self) -> io::Result<Vec<u8>> {
+        let mut bytes = Vec::with_capacity(self.serialized_len_estimate());
+        self.to_writer(&mut bytes)?;
+        Ok(bytes)
+    }
+
+    /// Writes the value as JSON to `writer`.
+    ///
+    /// The output is produced in many small writes, so an unbuffered sink
+    /// such as a `File` or `TcpStream` should be wrapped in a `BufWriter`
+    /// first, or passed to [`Value::to_buffered_writer`] instead.
+    ///
+    /// Fails with [`io::ErrorKind::InvalidData`] on numbers that are not
+    /// finite, as described for [`WriteOptions::non_finite`].
+    pub fn to_writer<W: Write>(&self, writer: W) -> io::Result<()> {
+        self.to_writer_with_options(writer, &WriteOptions::default())
+    }
+
+    /// Like [`Value::to_writer`], but buffers the output internally.
+    pub fn to_buffered_writer<W: Write>(&self, writer: W) -> io::Result<()> {
+        let mut writer = BufWriter::new(writer);
+        self.to_writer(&mut writer)?;
+        writer.flush()
+    }
+
+    /// Compares two values structurally, but allows floats to differ by
+    /// `epsilon`.
+    ///
+    /// Two numbers `a` and `b` are considered equal if `|a - b| <= epsilon`
+    /// (absolute tolerance) or `|a - b| <= epsilon * max(|a|, |b|)` (relative
+    /// tolerance), so the same `epsilon` works for both small and large
+    /// magnitudes. Two integers are always compared exactly. Everything
+    /// else, including object keys, must match exactly.
+    pub fn approx_eq(&self, other: &Value, epsilon: f64) -> bool {
+        match (self, other) {
+            (Value::Number(a), Value::Number(b)) if a.is_f64() || b.is_f64() => {
+                let (a, b) = (a.as_f64(), b.as_f64());
+                let diff = (a - b).abs();
+                diff <= epsilon || diff <= epsilon * a.abs().max(b.abs())
+            }
+            (Value::Array(a), Value::Array(b)) => {
+                a.len() == b.len() && a.iter().zip(b.iter()).all(|(a, b)| a.approx_eq(b, epsilon))
+            }
+            (Value::Object(a), Value::Object(b)) => {
+                a.len() == b.len()
+                    && a.as_inner()
+                        .iter()
+                        .zip(b.as_inner())
+                        .all(|((ka, va), (kb, vb))| ka == kb && va.approx_eq(vb, epsilon))
+            }
+            (a, b) => a == b,
+        }
+    }
+
+    /// Renders a scalar as a string: strings without quotes, and other
+    /// scalars as their JSON text, e.g. `"null"`. Returns `None` for arrays
+    /// and objects.
+    ///
+    /// Lone surrogates in strings are replaced with U+FFFD.
+    pub fn coerce_to_string(&self) -> Option<String> {
+        match self {
+            Value::Null => Some("null".to_owned()),
+            Value::Bool(v) => Some(v.to_string()),
+            Value::Number(v) => Some(v.to_string()),
+            Value::String(v) => Some(v.clone().into_string_lossy()),
+            Value::Array(_) | Value::Object(_) => None,
+        }
+    }
+
+    /// Returns whether `self` is an object containing `key`.
+    pub fn has_key(&self, key: &str) -> bool {
+        match self {
+            Value::Object(obj) => obj.contains_key(key),
+            _ => false,
+        }
+    }
+
+    /// Returns whether `self` is an array containing `needle`.
+    pub fn array_contains(&self, needle: &Value) -> bool {
+        match self {
+            Value::Array(arr) => arr.contains(needle),
+            _ => false,
+        }
+    }
+
+    /// Returns whether `needle` equals `self` or any value nested inside it.
+    ///
+    /// Whole subtrees are compared, so a string needle does not match a
+    /// substring of a longer string.
+    pub fn deep_contains(&self, needle: &Value) -> bool {
+        let mut stack = vec![self];
+        while let Some(value) = stack.pop() {
+            if value == needle {
+                return true;
+            }
+            match value {
+                Value::Array(arr) => stack.extend(arr.iter()),
+                Value::Object(obj) => stack.extend(obj.as_inner().values()),
+                _ => {}
+            }
+        }
+        false
+    }
+
+    /// Calls `f` on every scalar in the tree, without recursing.
+    fn for_each_scalar_mut(&mut self, mut f: impl FnMut(&mut Value)) {
+        let mut stack = vec![self];
+        while let Some(value) = stack.pop() {
+            match value {
+                Value::Array(arr) => stack.extend(arr.iter_mut().rev()),
+                Value::Object(obj) => stack.extend(obj.as_inner_mut().values_mut().rev()),
+                _ => f(value),
+            }
+        }
+    }
+
+    /// Sorts the elements of every array in the tree, like
+    /// [`Options::sort_arrays`] does while parsing.
+    pub fn sort_arrays(&mut self) {
+        // The tree is taken apart and rebuilt without recursing, so that
+        // every array is sorted after its elements.
+        enum Frame {
+            Array(Vec<Value>, <Array as IntoIterator>::IntoIter),
+            Object(Object, <Object as IntoIterator>::IntoIter, JsonString),
+        }
+
+        let mut stack = Vec::new();
+        let mut next = std::mem::replace(self, Value::Null);
+        loop {
+            let mut value = match next {
+                Value::Array(arr) => {
+                    stack.push(Frame::Array(Vec::with_capacity(arr.len()), arr.into_iter()));
+                    None
+                }
+                Value::Object(obj) => {
+                    stack.push(Frame::Object(
+                        Object::new(),
+                        obj.into_iter(),
+                        JsonString::new(),
+                    ));
+                    None
+                }
+                v => Some(v),
+            };
+
+            next = loop {
+                let Some(frame) = stack.last_mut() else {
+                    *self = value.unwrap();
+                    return;
+                };
+                match frame {
+                    Frame::Array(items, rest) => {
+                        items.extend(value.take());
+                        if let Some(v) = rest.next() {
+                            break v;
+                        }
+                        items.sort();
+                        value = Some(Value::Array(std::mem::take(items).into()));
+                    }
+                    Frame::Object(obj, rest, key) => {
+                        if let Some(v) = value.take() {
+                            obj.insert(std::mem::take(key), v);
+                        }
+                        if let Some((k, v)) = rest.next() {
+                            *key = k;
+                            break v;
+                        }
+                        value = Some(Value::Object(std::mem::take(obj)));
+                    }
+                }
+                stack.pop();
+            };
+        }
+    }
+
+    /// Passes the value through `f`, for chaining transformations.
+    pub fn map(self, f: impl FnOnce(Value) -> Value) -> Value {
+        f(self)
+    }
+
+    /// Replaces every scalar in the tree with the result of `f`, which may be
+    /// of a different type. Values returned by `f` are not visited again, and
+    /// empty arrays and objects are kept as they are.
+    pub fn map_leaves(mut self, mut f: impl FnMut(Value) -> Value) -> Value {
+        self.for_each_scalar_mut(|value| *value = f(std::mem::replace(value, Value::Null)));
+        self
+    }
+
+    /// Applies `f` to every string value in the tree. Object keys are left
+    /// unchanged.
+    pub fn map_strings(&mut self, mut f: impl FnMut(&mut JsonString)) {
+        self.for_each_scalar_mut(|value| {
+            if let Value::String(s) = value {
+                f(s);
+            }
+        });
+    }
+
+    /// Applies `f` to every number in the tree.
+    pub fn map_numbers(&mut self, mut f: impl FnMut(&mut Number)) {
+        self.for_each_scalar_mut(|value| {
+            if let Value::Number(n) = value {
+                f(n);
+            }
+        });
+    }
+
+    /// Returns the inner object, or `self` if it isn't an object.
+    pub fn into_object(self) -> Result<Object, Value> {
+        match self {
+            Value::Object(obj) => Ok(obj),
+            v => Err(v),
+        }
+    }
+
+    /// Returns the inner array, or `self` if it isn't an array.
+    pub fn into_array(self) -> Result<Array, Value> {
+        match self {
+            Value::Array(arr) => Ok(arr),
+            v => Err(v),
+        }
+    }
+
+    /// Returns the inner string, or `self` if it isn't a string.
+    pub fn into_string(self) -> Result<JsonString, Value> {
+        match self {
+            Value::String(s) => Ok(s),
+            v => Err(v),
+        }
+    }
+
+    /// Returns the inner number, or `self` if it isn't a number.
+    pub fn into_number(self) -> Result<Number, Value> {
+        match self {
+            Value::Number(n) => Ok(n),
+            v => Err(v),
+        }
+    }
+
+    /// Returns the inner bool, or `self` if it isn't a bool.
+    pub fn into_bool(self) -> Result<bool, Value> {
+        match self {
+            Value::Bool(b) => Ok(b),
+            v => Err(v),
+        }
+    }
+
+    /// Returns the object in `self`, replacing `Null` with an empty object
+    /// first.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` is neither an object nor `Null`.
+    pub fn as_object_mut_or_insert(&mut self) -> &mut Object {
+        if let Value::Null = self {
+            *self = Value::Object(Object::new());
+        }
+        match self {
+            Value::Object(obj) => obj,
+            _ => panic!("expected an object or null"),
+        }
+    }
+
+    /// Returns the array in `self`, replacing `Null` with an empty array
+    /// first.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` is neither an array nor `Null`.
+    pub fn as_array_mut_or_insert(&mut self) -> &mut Array {
+        if let Value::Null = self {
+            *self = Value::Array(Array::new());
+        }
+        match self {
+            Value::Array(arr) => arr,
+            _ => panic!("expected an array or null"),
+        }
+    }
+
+    /// Parses `bytes` into a tree where every node carries the byte range of
+    /// its source text.
+    pub fn from_json_with_spans(bytes: &[u8]) -> Result<Spanned, Error> {
+        Self::from_json_with_spans_and_options(bytes, &Options::default())
+    }
+
+    /// Like [`Value::from_json_with_spans`], but parses according to
+    /// `options`.
+    ///
+    /// [`Options::sort_arrays`] is ignored, as the spans are kept in document
+    /// order.
+    pub fn from_json_with_spans_and_options(
+        bytes: &[u8],
+        options: &Options,
+    ) -> Result<Spanned, Error> {
+        Reader::read_all_with_options(bytes, options, |reader| {
+            reader.skip_whitespace();
+            if reader.at_end() {
+                return Err(ErrorKind::EmptyInput);
+            }
+            read_spanned(reader)
+        })
+    }
+
+    /// Finds the first array or object in `bytes` that parses, e.g. JSON
+    /// embedded in a log line, and returns it with its byte range.
+    ///
+    /// Every `[` and `{` is tried in turn until one starts a valid value, so
+    /// this can be slow on large inputs with many false starts.
+    pub fn find_in(bytes: &[u8]) -> Option<(Value, Range<usize>)> {
+        let options = Options::default();
+        let mut start = 0;
+        while let Some(i) = bytes[start..].iter().position(|&b| b == b'[' || b == b'{') {
+            start += i;
+            let mut reader = Reader::new(&bytes[start..], &options);
+            let value = match bytes[start] {
+                b'[' => read_array(&mut reader).map(Value::Array),
+                _ => read_object(&mut reader).map(Value::Object),
+            };
+            if let Ok(value) = value {
+                return Some((value, start..start + reader.offset()));
+            }
+            start += 1;
+        }
+        None
+    }
+}
+
+static NULL: Value = Value::Null;
+
+/// Indexes into an object by key.
+///
+/// Unlike `HashMap`, this never panics: if `self` is not an object or the key
+/// is missing, a reference to `Value::Null` is returned. Use [`Object::get`]
+/// to tell these cases apart.
+impl Index<&str> for Value {
+    type Output = Value;
+
+    fn index(&self, key: &str) -> &Self::Output {
+        match self {
+            Value::Object(obj) => obj.get(key).unwrap_or(&NULL),
+            _ => &NULL,
+        }
+    }
+}
+
+/// Indexes into an array by position.
+///
+/// Unlike `Vec`, this never panics: if `self` is not an array or the index is
+/// out of bounds, a reference to `Value::Null` is returned. Use `<[_]>::get`
+/// on the [`Array`] to tell these cases apart.
+impl Index<usize> for Value {
+    type Output = Value;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        match self {
+            Value::Array(arr) => arr.get(index).unwrap_or(&NULL),
+            _ => &NULL,
+        }
     }
 }
 
+impl TryFrom<&[u8]> for Value {
+    type Error = Error;
+
+    fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
+        Self::from_json(value)
+    }
+}
+
+impl TryFrom<Vec<u8>> for Value {
+    type Error = Error;
+
+    fn try_from(value: Vec<u8>) -> Result<Self, Self::Error> {
+        Self::from_json(&value)
+    }
+}
+
+/// Numbers that are not finite are written as `NaN`, `Infinity` or
+/// `-Infinity`, which is not valid JSON; use
+/// [`Value::to_string_with_options`] to reject or replace them.
 impl Display for Value {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -144,11 +758,29 @@ impl Display for Value {
     }
 }
 
+impl Debug for Value {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Value::Null => write!(f, "Null"),
+            Value::Bool(v) => write!(f, "Bool({v})"),
+            Value::Number(v) => write!(f, "Number({v})"),
+            Value::String(v) => write!(f, "String({v})"),
+            Value::Array(v) => Debug::fmt(v, f),
+            Value::Object(v) => Debug::fmt(v, f),
+        }
+    }
+}
+
 #[cfg(feature = "serde_json")]
 pub use serde_json;
 
+/// A string with a lone surrogate, which `serde_json` can't represent.
 #[cfg(feature = "serde_json")]
-pub struct InvalidUnicodeString(pub JsonString);
+pub struct InvalidUnicodeString {
+    /// JSON Pointer to the string, or to the member if the string is its key.
+    pub path: String,
+    pub string: JsonString,
+}
 
 #[cfg(feature = "serde_json")]
 pub struct InvalidSerdeJsonNumber(pub serde_json::Number);
@@ -157,15 +789,67 @@ pub struct InvalidSerdeJsonNumber(pub serde_json::Number);
 impl TryFrom<serde_json::Value> for Value {
     type Error = InvalidSerdeJsonNumber;
 
+    /// Converts iteratively, so deeply nested values can't overflow the stack.
     fn try_from(value: serde_json::Value) -> Result<Self, Self::Error> {
-        Ok(match value {
-            serde_json::Value::Null => Value::Null,
-            serde_json::Value::Bool(b) => Value::Bool(b),
-            serde_json::Value::Number(v) => Value::Number(v.try_into()?),
-            serde_json::Value::String(s) => Value::String(JsonString::from(s)),
-            serde_json::Value::Array(arr) => Value::Array(Array::try_from(arr)?),
-            serde_json::Value::Object(map) => Value::Object(Object::try_from(map)?),
-        })
+        enum Frame {
+            Array(Vec<Value>, std::vec::IntoIter<serde_json::Value>),
+            Object(
+                BTreeMap<JsonString, Value>,
+                serde_json::map::IntoIter,
+                JsonString,
+            ),
+        }
+
+        let mut stack = Vec::new();
+        let mut next = value;
+        loop {
+            let mut value = match next {
+                serde_json::Value::Null => Some(Value::Null),
+                serde_json::Value::Bool(b) => Some(Value::Bool(b)),
+                serde_json::Value::Number(v) => Some(Value::Number(v.try_into()?)),
+                serde_json::Value::String(s) => Some(Value::String(JsonString::from(s))),
+                serde_json::Value::Array(arr) => {
+                    stack.push(Frame::Array(Vec::with_capacity(arr.len()), arr.into_iter()));
+                    None
+                }
+                serde_json::Value::Object(map) => {
+                    stack.push(Frame::Object(
+                        BTreeMap::new(),
+                        map.into_iter(),
+                        JsonString::new(),
+                    ));
+                    None
+                }
+            };
+
+            // Add the finished value to its parent, and finish every parent
+            // without more children.
+            next = loop {
+                let Some(frame) = stack.last_mut() else {
+                    return Ok(value.unwrap());
+                };
+                match frame {
+                    Frame::Array(items, rest) => {
+                        items.extend(value.take());
+                        if let Some(v) = rest.next() {
+                            break v;
+                        }
+                        value = Some(Value::Array(std::mem::take(items).into()));
+                    }
+                    Frame::Object(map, rest, key) => {
+                        if let Some(v) = value.take() {
+                            map.insert(std::mem::take(key), v);
+                        }
+                        if let Some((k, v)) = rest.next() {
+                            *key = k.into();
+                            break v;
+                        }
+                        value = Some(Value::Object(std::mem::take(map).into()));
+                    }
+                }
+                stack.pop();
+            };
+        }
     }
 }
 
@@ -173,17 +857,125 @@ impl TryFrom<serde_json::Value> for Value {
 impl TryFrom<Value> for serde_json::Value {
     type Error = InvalidUnicodeString;
 
+    /// Converts iteratively, so deeply nested values can't overflow the stack.
     fn try_from(value: Value) -> Result<Self, Self::Error> {
-        Ok(match value {
-            Value::Null => serde_json::Value::Null,
-            Value::Bool(b) => serde_json::Value::Bool(b),
-            Value::Number(v) => serde_json::Value::Number(v.into()),
-            Value::String(s) => {
-                serde_json::Value::String(s.into_string().map_err(InvalidUnicodeString)?)
+        enum Frame {
+            Array(Vec<serde_json::Value>, <Array as IntoIterator>::IntoIter),
+            Object(
+                serde_json::Map<String, serde_json::Value>,
+                <Object as IntoIterator>::IntoIter,
+                String,
+            ),
+        }
+
+        /// The pointer to the value being converted.
+        fn path(stack: &[Frame]) -> String {
+            let mut path = String::new();
+            for frame in stack {
+                match frame {
+                    Frame::Array(items, _) => push_index(&mut path, items.len()),
+                    Frame::Object(_, _, key) => push_token(&mut path, key),
+                }
             }
-            Value::Array(arr) => serde_json::Value::Array(arr.try_into()?),
-            Value::Object(obj) => serde_json::Value::Object(obj.try_into()?),
-        })
+            path
+        }
+
+        let mut stack = Vec::new();
+        let mut next = value;
+        loop {
+            let mut value = match next {
+                Value::Null => Some(serde_json::Value::Null),
+                Value::Bool(b) => Some(serde_json::Value::Bool(b)),
+                // Like `serde_json`, numbers it can't represent become null.
+                Value::Number(v) => Some(
+                    serde_json::Number::try_from(v)
+                        .map_or(serde_json::Value::Null, serde_json::Value::Number),
+                ),
+                Value::String(s) => Some(serde_json::Value::String(s.into_string().map_err(
+                    |string| InvalidUnicodeString {
+                        path: path(&stack),
+                        string,
+                    },
+                )?)),
+                Value::Array(arr) => {
+                    stack.push(Frame::Array(Vec::with_capacity(arr.len()), arr.into_iter()));
+                    None
+                }
+                Value::Object(obj) => {
+                    stack.push(Frame::Object(
+                        serde_json::Map::new(),
+                        obj.into_iter(),
+                        String::new(),
+                    ));
+                    None
+                }
+            };
+
+            // Add the finished value to its parent, and finish every parent
+            // without more children.
+            next = loop {
+                let Some(frame) = stack.last_mut() else {
+                    return Ok(value.unwrap());
+                };
+                match frame {
+                    Frame::Array(items, rest) => {
+                        items.extend(value.take());
+                        if let Some(v) = rest.next() {
+                            break v;
+                        }
+                        value = Some(serde_json::Value::Array(std::mem::take(items)));
+                    }
+                    Frame::Object(map, rest, key) => {
+                        if let Some(v) = value.take() {
+                            map.insert(std::mem::take(key), v);
+                        }
+                        if let Some((k, v)) = rest.next() {
+                            *key = match k.into_string() {
+                                Ok(k) => k,
+                                Err(string) => {
+                                    let mut path = path(&stack[..stack.len() - 1]);
+                                    push_key(&mut path, &string);
+                                    return Err(InvalidUnicodeString { path, string });
+                                }
+                            };
+                            break v;
+                        }
+                        value = Some(serde_json::Value::Object(std::mem::take(map)));
+                    }
+                }
+                stack.pop();
+            };
+        }
+    }
+}
+
+/// Compares structurally. Numbers are compared by value, so `1` equals
+/// `1.0`, and strings with lone surrogates never equal a `serde_json` string.
+#[cfg(feature = "serde_json")]
+impl PartialEq<serde_json::Value> for Value {
+    fn eq(&self, other: &serde_json::Value) -> bool {
+        match (self, other) {
+            (Value::Null, serde_json::Value::Null) => true,
+            (Value::Bool(a), serde_json::Value::Bool(b)) => a == b,
+            (Value::Number(a), serde_json::Value::Number(b)) => {
+                Number::try_from(b.clone()).is_ok_and(|b| *a == b)
+            }
+            (Value::String(a), serde_json::Value::String(b)) => a.as_str() == Some(b),
+            (Value::Array(a), serde_json::Value::Array(b)) => {
+                a.len() == b.len() && a.iter().zip(b).all(|(a, b)| a == b)
+            }
+            (Value::Object(a), serde_json::Value::Object(b)) => {
+                a.len() == b.len() && b.iter().all(|(k, b)| a.get(k).is_some_and(|a| a == b))
+            }
+            _ => false,
+        }
+    }
+}
+
+#[cfg(feature = "serde_json")]
+impl PartialEq<Value> for serde_json::Value {
+    fn eq(&self, other: &Value) -> bool {
+        other == self
     }
 }
 
@@ -237,6 +1029,8 @@ mod test {
         let leaf = prop_oneof![
             Just(Value::Null),
             any::<bool>().prop_map(Value::Bool),
+            any::<u64>().prop_map(Value::from),
+            any::<i64>().prop_map(Value::from),
             any::<f64>().prop_filter_map("non-finite f64", |v| Value::try_from(v).ok()),
             arb_string().prop_map(Value::String),
         ];
@@ -249,6 +1043,560 @@ mod test {
         })
     }
 
+    #[test]
+    fn test_constructors() {
+        assert_eq!(Value::null(), Value::Null);
+        assert_eq!(Value::bool(true), Value::Bool(true));
+        assert_eq!(Value::string("a"), Value::from("a"));
+        assert_eq!(Value::string(String::from("a")), Value::from("a"));
+        assert_eq!(Value::array(), Value::from_json(b"[]").unwrap());
+        assert_eq!(Value::object(), Value::from_json(b"{}").unwrap());
+
+        let mut value = Value::object();
+        value
+            .as_object_mut_or_insert()
+            .insert("a".into(), Value::array());
+        assert_eq!(value.to_string(), r#"{"a":[]}"#);
+    }
+
+    #[test]
+    fn test_try_from_bytes() {
+        assert_eq!(
+            Value::try_from(&b"[true]"[..]),
+            Ok(vec![true.into()].into())
+        );
+        assert_eq!(Value::try_from(b"null".to_vec()), Ok(Value::Null));
+        assert_eq!(
+            Value::try_from(b"nul".to_vec()),
+            Err(Error::at(ErrorKind::UnexpectedEof, 0))
+        );
+    }
+
+    #[test]
+    fn test_index() {
+        let v = Value::from_json(br#"{"a": [1, {"b": true}], "c": null}"#).unwrap();
+        assert_eq!(v["a"][1]["b"], Value::Bool(true));
+        assert_eq!(v["c"], Value::Null);
+        assert_eq!(v["missing"], Value::Null);
+        assert_eq!(v["a"][5], Value::Null);
+        assert_eq!(v[0], Value::Null);
+        assert_eq!(v["a"]["b"], Value::Null);
+        assert_eq!(v["missing"]["deeper"][3], Value::Null);
+    }
+
+    #[test]
+    fn test_to_writer() {
+        let value = Value::from_json(br#"{"a": ["x\ny", 1.5, null], "b": "\ud800"}"#).unwrap();
+
+        let mut unbuffered = Vec::new();
+        value.to_writer(&mut unbuffered).unwrap();
+        assert_eq!(unbuffered, value.to_string().as_bytes());
+
+        let mut buffered = Vec::new();
+        value.to_buffered_writer(&mut buffered).unwrap();
+        assert_eq!(buffered, unbuffered);
+    }
+
+    #[test]
+    fn test_extra_whitespace() {
+        let json = b"[1,\x0b2]\x0b";
+        assert_eq!(
+            Value::from_json(json),
+            Err(Error::at(ErrorKind::UnexpectedStartOfValue(0x0b), 3))
+        );
+
+        let options = Options {
+            extra_whitespace: b"\x0b",
+            ..Options::default()
+        };
+        assert_eq!(
+            Value::from_json_with_options(json, &options),
+            Ok(vec![1.into(), 2.into()].into())
+        );
+
+        let extra = vec![0x0b];
+        let options = Options {
+            extra_whitespace: &extra,
+            ..Options::default()
+        };
+        assert!(Value::from_json_with_options(json, &options).is_ok());
+    }
+
+    #[test]
+    fn test_allow_unquoted_values() {
+        let json = b"{\"a\": fast, \"b\": [x_1, $y, true, null, nullish, falsey]}";
+        assert_eq!(
+            Value::from_json(json),
+            Err(Error::at(ErrorKind::ExpectedFalse, 11))
+        );
+
+        let options = Options {
+            allow_unquoted_values: true,
+            ..Options::default()
+        };
+        assert_eq!(
+            Value::from_json_with_options(json, &options),
+            Value::from_json(
+                br#"{"a": "fast", "b": ["x_1", "$y", true, null, "nullish", "falsey"]}"#
+            )
+        );
+        assert_eq!(
+            Value::from_json_with_options(b"[a b]", &options),
+            Err(Error::at(ErrorKind::ExpectedCommaOrRightBracket(b'b'), 4))
+        );
+        assert_eq!(
+            Value::from_json_with_options(b"[-a]", &options),
+            Err(Error::at(ErrorKind::InvalidDigit(b'a'), 3))
+        );
+    }
+
+    #[test]
+    fn test_value_transform() {
+        let options = Options {
+            value_transform: Some(|v| match v {
+                Value::String(s) => Value::String(s.trim().into()),
+                v => v,
+            }),
+            ..Options::default()
+        };
+        assert_eq!(
+            Value::from_json_with_options(br#"{" k ": [" a ", 1, {"b": "\tb\n"}]}"#, &options),
+            Value::from_json(br#"{" k ": ["a", 1, {"b": "b"}]}"#)
+        );
+        assert_eq!(
+            Value::from_json_with_options(br#"" top ""#, &options),
+            Ok("top".into())
+        );
+    }
+
+    #[test]
+    fn test_case_insensitive_literals() {
+        let json = b"[NULL, True, FALSE, null]";
+        assert_eq!(
+            Value::from_json(json),
+            Err(Error::at(ErrorKind::UnexpectedStartOfValue(b'N'), 1))
+        );
+        assert_eq!(
+            Value::from_json(b"nULL"),
+            Err(Error::at(ErrorKind::ExpectedNull, 4))
+        );
+
+        let options = Options {
+            case_insensitive_literals: true,
+            ..Options::default()
+        };
+        assert_eq!(
+            Value::from_json_with_options(json, &options),
+            Value::from_json(b"[null, true, false, null]")
+        );
+        assert_eq!(
+            Value::from_json_with_options(b"Nope", &options),
+            Err(Error::at(ErrorKind::ExpectedNull, 4))
+        );
+        for options in [Options::default(), options] {
+            assert_eq!(
+                Value::from_json_with_options(b"nullx", &options),
+                Err(Error::at(ErrorKind::TrailingData, 4))
+            );
+            assert_eq!(
+                Value::from_json_with_options(b"[nullx]", &options),
+                Err(Error::at(ErrorKind::ExpectedCommaOrRightBracket(b'x'), 6))
+            );
+        }
+
+        let options = Options {
+            case_insensitive_literals: true,
+            allow_unquoted_values: true,
+            ..Options::default()
+        };
+        assert_eq!(
+            Value::from_json_with_options(b"[True, Nullx]", &options),
+            Value::from_json(br#"[true, "Nullx"]"#)
+        );
+    }
+
+    #[test]
+    fn test_empty_input() {
+        assert_eq!(
+            Value::from_json(b""),
+            Err(Error::at(ErrorKind::EmptyInput, 0))
+        );
+        assert_eq!(
+            Value::from_json(b"   "),
+            Err(Error::at(ErrorKind::EmptyInput, 3))
+        );
+        assert_eq!(
+            Value::from_json_with_spans(b"\n").err(),
+            Some(Error::at(ErrorKind::EmptyInput, 1))
+        );
+        assert_eq!(
+            Value::from_json(b"["),
+            Err(Error::at(ErrorKind::UnexpectedEof, 1))
+        );
+    }
+
+    #[test]
+    fn test_find_in() {
+        let text = br#"INFO [worker 3] done: {"id": 7, "tags": ["a"]} (took {3} ms)"#;
+        let (value, range) = Value::find_in(text).unwrap();
+        assert_eq!(
+            value,
+            Value::from_json(br#"{"id": 7, "tags": ["a"]}"#).unwrap()
+        );
+        assert_eq!(&text[range], br#"{"id": 7, "tags": ["a"]}"#);
+
+        let (value, range) = Value::find_in(b"x = [1, [2] ;").unwrap();
+        assert_eq!(value, Value::from_json(b"[2]").unwrap());
+        assert_eq!(range, 8..11);
+
+        assert_eq!(Value::find_in(b"no json {here"), None);
+        assert_eq!(Value::find_in(b""), None);
+    }
+
+    #[test]
+    fn test_allow_trailing_data() {
+        let json = b"1 garbage";
+        assert_eq!(
+            Value::from_json(json),
+            Err(Error::at(ErrorKind::TrailingData, 2))
+        );
+
+        let options = Options {
+            allow_trailing_data: true,
+            ..Options::default()
+        };
+        assert_eq!(Value::from_json_with_options(json, &options), Ok(1.into()));
+    }
+
+    #[test]
+    fn test_strip_xssi_prefix() {
+        let json = b")]}'\n{\"a\": 1}";
+        assert_eq!(
+            Value::from_json(json),
+            Err(Error::at(ErrorKind::UnexpectedStartOfValue(b')'), 0))
+        );
+
+        let options = Options {
+            strip_xssi_prefix: true,
+            ..Options::default()
+        };
+        assert_eq!(
+            Value::from_json_with_options(json, &options),
+            Value::from_json(br#"{"a": 1}"#)
+        );
+    }
+
+    #[test]
+    fn test_from_json_checked() {
+        let (value, warnings) =
+            Value::from_json_checked(b"[9007199254740993, 0.1, 1.50e1, 0e99999999999999999999]")
+                .unwrap();
+        assert_eq!(value[0], Value::from(9007199254740993u64));
+        assert_eq!(warnings, vec![]);
+
+        let (_, warnings) =
+            Value::from_json_checked(b"[9007199254740993.0, 12345678901234567890123]").unwrap();
+        assert_eq!(
+            warnings,
+            vec![
+                Warning::PrecisionLoss {
+                    offset: 1,
+                    original: "9007199254740993.0".to_owned(),
+                },
+                Warning::PrecisionLoss {
+                    offset: 21,
+                    original: "12345678901234567890123".to_owned(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_serialized_len_estimate() {
+        for json in [
+            &b"null"[..],
+            b"-12.5e-7",
+            br#""a\"b\u0000\ud800\u00e6""#,
+            br#"[]"#,
+            br#"{"a": [1, 2.5, true, {"b": null}], "c\nd": "\u1234"}"#,
+        ] {
+            let value = Value::from_json(json).unwrap();
+            let bytes = value.to_bytes().unwrap();
+            assert_eq!(bytes, value.to_string().as_bytes());
+            assert!(value.serialized_len_estimate() >= bytes.len());
+        }
+    }
+
+    #[test]
+    fn test_approx_eq() {
+        let a = Value::from_json(br#"{"x": [1.0000001, "s"], "y": null}"#).unwrap();
+        let b = Value::from_json(br#"{"x": [1.0, "s"], "y": null}"#).unwrap();
+        assert!(a.approx_eq(&b, 1e-6));
+        assert!(!a.approx_eq(&b, 1e-9));
+        assert_ne!(a, b);
+
+        let big_a = Value::from_json(b"1e20").unwrap();
+        let big_b = Value::from_json(b"1.0000001e20").unwrap();
+        assert!(big_a.approx_eq(&big_b, 1e-6));
+
+        assert!(!Value::from(1).approx_eq(&Value::from(2), 10.0));
+
+        let c = Value::from_json(br#"{"x": [1.0, "t"], "y": null}"#).unwrap();
+        assert!(!b.approx_eq(&c, 1.0));
+        let d = Value::from_json(br#"{"x": [1.0, "s"], "z": null}"#).unwrap();
+        assert!(!b.approx_eq(&d, 1.0));
+    }
+
+    #[test]
+    fn test_from_option() {
+        assert_eq!(Value::from(None::<i64>), Value::Null);
+        assert_eq!(Value::from(Some(5)), Value::Number(5.into()));
+        assert_eq!(Value::from(Some("a")), Value::from("a"));
+    }
+
+    #[test]
+    fn test_debug() {
+        let value = Value::from_json(br#"{"a": [1.5, "x\n", null], "b": {}, "c": true}"#).unwrap();
+        assert_eq!(
+            format!("{value:?}"),
+            r#"{"a": [Number(1.5), String("x\n"), Null], "b": {}, "c": Bool(true)}"#
+        );
+        assert_eq!(
+            format!("{:#?}", value["a"]),
+            "[\n    Number(1.5),\n    String(\"x\\n\"),\n    Null,\n]"
+        );
+    }
+
+    #[test]
+    fn test_collect() {
+        let value: Value = (1..=3).map(Value::from).collect();
+        assert_eq!(value, Value::from_json(b"[1, 2, 3]").unwrap());
+
+        let value: Value = [("b", 1), ("a", 2)]
+            .into_iter()
+            .map(|(k, v)| (JsonString::from(k), Value::from(v)))
+            .collect();
+        assert_eq!(value, Value::from_json(br#"{"a": 2, "b": 1}"#).unwrap());
+
+        let value: Value = std::iter::empty::<Value>().collect();
+        assert_eq!(value, Value::Array(Array::new()));
+    }
+
+    #[test]
+    fn test_sort_arrays() {
+        let mut value = Value::from_json(
+            br#"{"a": [3, [2, 1], {"b": [true, null]}, [1, 0]], "c": [["y", "x"], 1], "d": 1}"#,
+        )
+        .unwrap();
+        value.sort_arrays();
+        assert_eq!(
+            value,
+            Value::from_json(
+                br#"{"a": [3, [0, 1], [1, 2], {"b": [null, true]}], "c": [1, ["x", "y"]], "d": 1}"#
+            )
+            .unwrap()
+        );
+
+        let options = Options {
+            sort_arrays: true,
+            ..Options::default()
+        };
+        let json = br#"[[[3, 2], [1]], [[2], [1, 0]]]"#;
+        let mut value = Value::from_json(json).unwrap();
+        value.sort_arrays();
+        assert_eq!(Value::from_json_with_options(json, &options), Ok(value));
+    }
+
+    #[test]
+    fn test_map_strings_and_numbers() {
+        let mut value =
+            Value::from_json(br#"{"a": ["x", 1, {"b": "y"}], "c": -2.5, "d": null}"#).unwrap();
+        value.map_strings(|s| *s = s.clone().into_string_lossy().to_uppercase().into());
+        value.map_numbers(|n| *n = Number::try_from(-n.as_f64()).unwrap());
+        assert_eq!(
+            value,
+            Value::from_json(br#"{"a": ["X", -1, {"b": "Y"}], "c": 2.5, "d": null}"#).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_map_leaves() {
+        let value =
+            Value::from_json(br#"{"a": [1, {"b": true}, []], "c": "x", "d": null}"#).unwrap();
+        let value = value.map_leaves(|v| match v {
+            Value::String(s) => Value::String(s),
+            v => Value::from(v.to_string()),
+        });
+        assert_eq!(
+            value,
+            Value::from_json(br#"{"a": ["1", {"b": "true"}, []], "c": "x", "d": "null"}"#).unwrap()
+        );
+        assert_eq!(
+            value.map(|v| Value::Array(vec![v].into())).to_string(),
+            r#"[{"a":["1",{"b":"true"},[]],"c":"x","d":"null"}]"#
+        );
+    }
+
+    #[test]
+    fn test_has_key_and_array_contains() {
+        let value = Value::from_json(br#"{"a": [1, "b", null]}"#).unwrap();
+        assert!(value.has_key("a"));
+        assert!(!value.has_key("b"));
+        assert!(!value["a"].has_key("a"));
+
+        assert!(value["a"].array_contains(&"b".into()));
+        assert!(value["a"].array_contains(&Value::Null));
+        assert!(!value["a"].array_contains(&2.into()));
+        assert!(!value.array_contains(&Value::Null));
+    }
+
+    #[test]
+    fn test_deep_contains() {
+        let value =
+            Value::from_json(br#"{"a": [1, {"b": "needle haystack"}], "c": {"d": null}}"#).unwrap();
+        assert!(value.deep_contains(&value));
+        assert!(value.deep_contains(&Value::from_json(br#"{"b": "needle haystack"}"#).unwrap()));
+        assert!(value.deep_contains(&Value::from(1)));
+        assert!(value.deep_contains(&Value::Null));
+        assert!(!value.deep_contains(&Value::from("needle")));
+        assert!(!value.deep_contains(&Value::from(2)));
+    }
+
+    #[test]
+    fn test_coerce_to_string() {
+        let value = Value::from_json(br#"[null, true, false, 1.5, -2, "a\"b", [], {}]"#).unwrap();
+        let Value::Array(arr) = value else {
+            unreachable!()
+        };
+        let strings: Vec<_> = arr.iter().map(Value::coerce_to_string).collect();
+        assert_eq!(
+            strings,
+            [
+                Some("null".to_owned()),
+                Some("true".to_owned()),
+                Some("false".to_owned()),
+                Some("1.5".to_owned()),
+                Some("-2".to_owned()),
+                Some("a\"b".to_owned()),
+                None,
+                None,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_into_inner() {
+        let value = Value::from_json(br#"{"a": [1]}"#).unwrap();
+        assert_eq!(value.clone().into_array(), Err(value.clone()));
+        let mut obj = value.into_object().unwrap();
+        assert_eq!(
+            obj.remove("a").unwrap().into_array(),
+            Ok(vec![1.into()].into())
+        );
+
+        assert_eq!(Value::from("a").into_string(), Ok("a".into()));
+        assert_eq!(Value::from(1).into_string(), Err(1.into()));
+        assert_eq!(Value::from(1).into_number(), Ok(1.into()));
+        assert_eq!(Value::Null.into_number(), Err(Value::Null));
+        assert_eq!(Value::from(true).into_bool(), Ok(true));
+        assert_eq!(Value::from("true").into_bool(), Err("true".into()));
+    }
+
+    #[test]
+    fn test_or_insert() {
+        let mut value = Value::Null;
+        value
+            .as_object_mut_or_insert()
+            .insert("a".into(), Value::Null);
+        value
+            .as_object_mut_or_insert()
+            .get_mut("a")
+            .unwrap()
+            .as_array_mut_or_insert()
+            .push(1.into());
+        assert_eq!(value, Value::from_json(br#"{"a": [1]}"#).unwrap());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_or_insert_wrong_type() {
+        Value::from(1).as_array_mut_or_insert();
+    }
+
+    #[cfg(feature = "serde_json")]
+    #[test]
+    fn test_serde_json_round_trip() {
+        let value =
+            Value::from_json(br#"{"a": [1, -2, 1.5, {"b": null}], "c": "d", "e": [[]]}"#).unwrap();
+        let serde_value = serde_json::Value::try_from(value.clone()).ok().unwrap();
+        assert_eq!(
+            serde_value,
+            serde_json::json!({"a": [1, -2, 1.5, {"b": null}], "c": "d", "e": [[]]})
+        );
+        assert_eq!(Value::try_from(serde_value).ok(), Some(value));
+    }
+
+    #[cfg(feature = "serde_json")]
+    #[test]
+    fn test_serde_json_eq() {
+        let value = Value::from_json(br#"{"a": [1, 2.5, null, true], "b": "c"}"#).unwrap();
+        let serde_value = serde_json::json!({"b": "c", "a": [1, 2.5, null, true]});
+        assert_eq!(value, serde_value);
+        assert_eq!(serde_value, value);
+
+        assert_eq!(Value::from(1), serde_json::json!(1.0));
+        assert_ne!(Value::from(1), serde_json::json!(1.5));
+        assert_ne!(Value::from(1), serde_json::json!("1"));
+        assert_ne!(value, serde_json::json!({"a": [1, 2.5, null, true]}));
+        assert_ne!(value, serde_json::json!({"a": [1, 2.5, null], "b": "c"}));
+
+        let lone_surrogate = Value::from_json(br#""\ud800""#).unwrap();
+        assert_ne!(lone_surrogate, serde_json::json!("\u{fffd}"));
+    }
+
+    #[cfg(feature = "serde_json")]
+    #[test]
+    fn test_serde_json_invalid_unicode_path() {
+        let value = Value::from_json(br#"{"a": [1, {"b/c": ["x", "\ud800"]}], "d": "e"}"#).unwrap();
+        let e = serde_json::Value::try_from(value.clone()).err().unwrap();
+        assert_eq!(e.path, "/a/1/b~1c/1");
+        assert_eq!(Value::String(e.string), value["a"][1]["b/c"][1]);
+
+        let value = Value::from_json(br#"{"a": [{"\udc00x": 1}]}"#).unwrap();
+        let e = serde_json::Value::try_from(value).err().unwrap();
+        assert_eq!(e.path, "/a/0/\u{fffd}x");
+
+        let arr = Array::from_json(br#"[[], ["\ud800"]]"#).unwrap();
+        let e = Vec::<serde_json::Value>::try_from(arr).err().unwrap();
+        assert_eq!(e.path, "/1/0");
+
+        let e = serde_json::Value::try_from(Value::from_json(br#""\ud800""#).unwrap())
+            .err()
+            .unwrap();
+        assert_eq!(e.path, "");
+    }
+
+    #[cfg(feature = "serde_json")]
+    #[test]
+    fn test_serde_json_deep() {
+        const DEPTH: usize = 100_000;
+
+        let mut serde_value = serde_json::Value::Null;
+        for _ in 0..DEPTH {
+            serde_value = serde_json::Value::Array(vec![serde_value]);
+        }
+        let value = Value::try_from(serde_value).ok().unwrap();
+        let mut value = serde_json::Value::try_from(value).ok().unwrap();
+
+        // Take the value apart by hand, as dropping it would recurse.
+        let mut depth = 0;
+        while let serde_json::Value::Array(mut arr) = value {
+            value = arr.pop().unwrap();
+            depth += 1;
+        }
+        assert_eq!(depth, DEPTH);
+    }
+
     proptest! {
         #[test]
         fn test_value_to_string_and_back(value in arb_value()) {
@@ -1,24 +1,90 @@
-use std::{fmt::Display, hash::Hash, str};
+use std::{cmp::Ordering, fmt::Display, hash::Hash, str, str::FromStr};
 
-use crate::{error::Error, parser::Reader};
+use crate::{
+    error::{Error, ErrorKind, Warning},
+    options::OverflowPolicy,
+    parser::Reader,
+};
 
-#[derive(Debug, Clone, PartialEq, PartialOrd)]
+#[derive(Debug, Clone, Copy)]
+enum N {
+    PosInt(u64),
+    /// Always less than zero.
+    NegInt(i64),
+    /// Always finite, unless created with one of the constructors behind the
+    /// `non_finite` feature.
+    Float(f64),
+}
+
+/// A JSON number.
+///
+/// Integers that fit in an `i64` or `u64` are stored exactly, everything else
+/// is stored as an `f64`. Comparison and hashing use the mathematical value,
+/// so `1` and `1.0` are equal.
+#[derive(Debug, Clone)]
 pub struct Number {
-    inner: f64,
+    inner: N,
+}
+
+/// 2^53, the smallest float from which not all integers are representable.
+const F64_EXACT_INT_LIMIT: f64 = 9007199254740992.0;
+
+/// 2^127, the smallest float that is too large for an `i128`.
+const I128_LIMIT: f64 = 170141183460469231731687303715884105728.0;
+
+/// Orders floats by value, with NaN greater than everything else.
+fn cmp_floats(a: f64, b: f64) -> Ordering {
+    a.partial_cmp(&b)
+        .unwrap_or_else(|| a.is_nan().cmp(&b.is_nan()))
+}
+
+fn cmp_int_float(i: i128, f: f64) -> Ordering {
+    if f >= I128_LIMIT || f.is_nan() {
+        return Ordering::Less;
+    }
+    if f < -I128_LIMIT {
+        return Ordering::Greater;
+    }
+    let trunc = f.trunc();
+    i.cmp(&(trunc as i128))
+        .then_with(|| 0.0.partial_cmp(&(f - trunc)).unwrap())
+}
+
+impl PartialEq for Number {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
 }
 
 impl Eq for Number {}
 
-#[allow(clippy::derive_ord_xor_partial_ord)]
+impl PartialOrd for Number {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
 impl Ord for Number {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        self.partial_cmp(other).unwrap()
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.inner, other.inner) {
+            (N::Float(a), N::Float(b)) => cmp_floats(a, b),
+            (N::Float(a), _) => cmp_int_float(other.as_i128().unwrap(), a).reverse(),
+            (_, N::Float(b)) => cmp_int_float(self.as_i128().unwrap(), b),
+            _ => self.as_i128().cmp(&other.as_i128()),
+        }
     }
 }
 
 impl Hash for Number {
     fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
-        self.inner.to_bits().hash(state);
+        // Must agree with `Eq`, so integral floats hash like integers.
+        match self.inner {
+            // All NaNs are equal.
+            N::Float(v) if v.is_nan() => f64::NAN.to_bits().hash(state),
+            N::Float(v) if v.fract() != 0.0 || v.abs() >= I128_LIMIT => v.to_bits().hash(state),
+            N::Float(v) => (v as i128).hash(state),
+            _ => self.as_i128().unwrap().hash(state),
+        }
     }
 }
 
@@ -26,9 +92,124 @@ impl Number {
     pub fn from_json(bytes: &[u8]) -> Result<Self, Error> {
         Reader::read_all(bytes, read_number)
     }
+
+    /// Returns whether both numbers are stored the same way, unlike `==`
+    /// which compares their values. `1` and `1.0` are not exactly equal, nor
+    /// are `0.0` and `-0.0`.
+    ///
+    /// The source text is not kept, so `1.0` and `1e0` are exactly equal.
+    pub fn eq_exact(&self, other: &Self) -> bool {
+        match (self.inner, other.inner) {
+            (N::PosInt(a), N::PosInt(b)) => a == b,
+            (N::NegInt(a), N::NegInt(b)) => a == b,
+            (N::Float(a), N::Float(b)) => a.to_bits() == b.to_bits(),
+            _ => false,
+        }
+    }
+
+    pub fn is_u64(&self) -> bool {
+        matches!(self.inner, N::PosInt(_))
+    }
+
+    pub fn is_i64(&self) -> bool {
+        match self.inner {
+            N::PosInt(v) => i64::try_from(v).is_ok(),
+            N::NegInt(_) => true,
+            N::Float(_) => false,
+        }
+    }
+
+    pub fn is_f64(&self) -> bool {
+        matches!(self.inner, N::Float(_))
+    }
+
+    pub fn as_u64(&self) -> Option<u64> {
+        match self.inner {
+            N::PosInt(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    pub fn as_i64(&self) -> Option<i64> {
+        match self.inner {
+            N::PosInt(v) => v.try_into().ok(),
+            N::NegInt(v) => Some(v),
+            N::Float(_) => None,
+        }
+    }
+
+    /// Returns the number as an `f64`, which may lose precision for large
+    /// integers.
+    pub fn as_f64(&self) -> f64 {
+        match self.inner {
+            N::PosInt(v) => v as f64,
+            N::NegInt(v) => v as f64,
+            N::Float(v) => v,
+        }
+    }
+
+    /// Creates a float without checking that it is finite, which no parsed
+    /// number can be.
+    #[cfg(test)]
+    pub(crate) fn non_finite(v: f64) -> Self {
+        Self { inner: N::Float(v) }
+    }
+
+    /// Not a number, which compares equal to itself and greater than every
+    /// other number.
+    #[cfg(feature = "non_finite")]
+    pub fn nan() -> Self {
+        Self {
+            inner: N::Float(f64::NAN),
+        }
+    }
+
+    #[cfg(feature = "non_finite")]
+    pub fn infinity() -> Self {
+        Self {
+            inner: N::Float(f64::INFINITY),
+        }
+    }
+
+    #[cfg(feature = "non_finite")]
+    pub fn neg_infinity() -> Self {
+        Self {
+            inner: N::Float(f64::NEG_INFINITY),
+        }
+    }
+
+    /// Like `Number::try_from(v)`, but also accepts NaN and infinities.
+    #[cfg(feature = "non_finite")]
+    pub fn from_f64_lossy(v: f64) -> Self {
+        Self { inner: N::Float(v) }
+    }
+
+    /// Length of the serialized number.
+    pub(crate) fn serialized_len(&self) -> usize {
+        struct Counter(usize);
+
+        impl std::fmt::Write for Counter {
+            fn write_str(&mut self, s: &str) -> std::fmt::Result {
+                self.0 += s.len();
+                Ok(())
+            }
+        }
+
+        let mut counter = Counter(0);
+        std::fmt::write(&mut counter, format_args!("{self}")).unwrap();
+        counter.0
+    }
+
+    fn as_i128(&self) -> Option<i128> {
+        match self.inner {
+            N::PosInt(v) => Some(v.into()),
+            N::NegInt(v) => Some(v.into()),
+            N::Float(_) => None,
+        }
+    }
 }
 
-fn skip_digits(reader: &mut Reader) -> Result<bool, Error> {
+fn skip_digits(reader: &mut Reader) -> Result<bool, ErrorKind> {
     let mut found_digit = false;
     while let Some(b'0'..=b'9') = reader.peek_byte() {
         reader.read_byte()?;
@@ -37,9 +218,9 @@ fn skip_digits(reader: &mut Reader) -> Result<bool, Error> {
     Ok(found_digit)
 }
 
-fn skip_number(reader: &mut Reader) -> Result<(), Error> {
+fn skip_number(reader: &mut Reader) -> Result<(), ErrorKind> {
     match reader.peek_byte() {
-        None => return Err(Error::UnexpectedEof),
+        None => return Err(ErrorKind::UnexpectedEof),
         Some(b'-') => {
             reader.read_byte()?;
         }
@@ -52,14 +233,14 @@ fn skip_number(reader: &mut Reader) -> Result<(), Error> {
         b'1'..=b'9' => {
             skip_digits(reader)?;
         }
-        _ => return Err(Error::InvalidDigit(b)),
+        _ => return Err(ErrorKind::InvalidDigit(b)),
     }
 
     if reader.peek_byte() == Some(b'.') {
         reader.read_byte()?;
 
         if !skip_digits(reader)? {
-            return Err(Error::InvalidDigit(reader.read_byte()?));
+            return Err(ErrorKind::InvalidDigit(reader.read_byte()?));
         }
     }
 
@@ -71,41 +252,234 @@ fn skip_number(reader: &mut Reader) -> Result<(), Error> {
         }
 
         if !skip_digits(reader)? {
-            return Err(Error::InvalidDigit(reader.read_byte()?));
+            return Err(ErrorKind::InvalidDigit(reader.read_byte()?));
         }
     }
 
     Ok(())
 }
 
-// TODO: Add support for integers
-// Hard cases:
+fn parse_number(s: &str) -> Number {
+    if !s.contains(['.', 'e', 'E']) {
+        if let Ok(v) = s.parse::<u64>() {
+            return v.into();
+        }
+        // `-0` is left to the float parsing below to keep its sign.
+        if let Ok(v @ ..0) = s.parse::<i64>() {
+            return v.into();
+        }
+    }
+
+    Number {
+        inner: N::Float(s.parse().unwrap()),
+    }
+}
+
+/// Reads a hexadecimal or octal integer, or returns `None` if the input
+/// doesn't start with one.
+fn read_radix_integer(reader: &mut Reader) -> Result<Option<Number>, ErrorKind> {
+    let rest = reader.remaining();
+    let (negative, rest) = match rest.strip_prefix(b"-") {
+        Some(rest) => (true, rest),
+        None => (false, rest),
+    };
+    let radix = match rest.get(..2) {
+        Some(b"0x" | b"0X") => 16,
+        Some(b"0o" | b"0O") => 8,
+        _ => return Ok(None),
+    };
+    for _ in 0..2 + usize::from(negative) {
+        reader.read_byte().unwrap();
+    }
+
+    let mut int = Some(0u64);
+    let mut float = 0.0;
+    let mut digits = 0;
+    while let Some(d) = reader.peek_byte().and_then(|b| (b as char).to_digit(radix)) {
+        reader.read_byte().unwrap();
+        int = int
+            .and_then(|v| v.checked_mul(radix.into()))
+            .and_then(|v| v.checked_add(d.into()));
+        float = float * f64::from(radix) + f64::from(d);
+        digits += 1;
+    }
+    if digits == 0 {
+        return Err(ErrorKind::InvalidDigit(reader.read_byte()?));
+    }
+
+    let number = match (negative, int) {
+        (false, Some(v)) => v.into(),
+        (true, Some(0)) => 0.into(),
+        (true, Some(v)) if v <= i64::MIN.unsigned_abs() => (v as i64).wrapping_neg().into(),
+        _ => match overflowed_integer(reader, negative)? {
+            Some(v) => v,
+            None => Number::try_from(if negative { -float } else { float })
+                .map_err(|()| ErrorKind::InfiniteFloat)?,
+        },
+    };
+    Ok(Some(number))
+}
+
+/// Handles an integer that doesn't fit in a `u64` or `i64` according to
+/// [`Options::integer_overflow`], returning `None` to parse it as a float.
+///
+/// [`Options::integer_overflow`]: crate::Options::integer_overflow
+fn overflowed_integer(reader: &Reader, negative: bool) -> Result<Option<Number>, ErrorKind> {
+    match reader.options().integer_overflow {
+        OverflowPolicy::Float => Ok(None),
+        OverflowPolicy::Error => Err(ErrorKind::IntegerOverflow),
+        OverflowPolicy::Saturate if negative => Ok(Some(i64::MIN.into())),
+        OverflowPolicy::Saturate => Ok(Some(u64::MAX.into())),
+    }
+}
+
+// TODO: Hard cases for integers:
 // `0.123e3` -> `123u64`
 // `1000000000000000000000000000e-10` -> `100000000000000000u64`
-pub(crate) fn read_number(reader: &mut Reader) -> Result<Number, Error> {
+pub(crate) fn read_number(reader: &mut Reader) -> Result<Number, ErrorKind> {
+    if reader.options().allow_radix_integers || reader.dialect().radix_integers() {
+        if let Some(v) = read_radix_integer(reader)? {
+            return Ok(v);
+        }
+    }
+
+    let offset = reader.offset();
     let (slice, _) = reader.parse_slice(skip_number)?;
     let s = str::from_utf8(slice).unwrap();
-    let v: f64 = s.parse().unwrap();
 
-    if !v.is_finite() {
-        return Err(Error::InfiniteFloat);
+    if let Some(number_parser) = reader.options().number_parser {
+        return number_parser(s);
+    }
+
+    if reader.options().integer_overflow != OverflowPolicy::Float
+        && !s.contains(['.', 'e', 'E'])
+        && s.parse::<u64>().is_err()
+        && s.parse::<i64>().is_err()
+    {
+        if let Some(v) = overflowed_integer(reader, s.starts_with('-'))? {
+            return Ok(v);
+        }
     }
 
-    Ok(Number { inner: v })
+    let v = parse_number(s);
+    if !v.as_f64().is_finite() {
+        return Err(ErrorKind::InfiniteFloat);
+    }
+
+    if let (Some(warnings), N::Float(f)) = (&mut reader.warnings, v.inner) {
+        if significant_digits(s) != significant_digits(&format!("{f:e}")) {
+            warnings.push(Warning::PrecisionLoss {
+                offset,
+                original: s.to_owned(),
+            });
+        }
+    }
+
+    Ok(v)
 }
 
+/// Normalizes a number token to its significant digits `d` and exponent `e`,
+/// such that its magnitude is `0.d * 10^e`.
+///
+/// Returns `None` if the exponent doesn't fit in an `i64`.
+fn significant_digits(s: &str) -> Option<(String, i64)> {
+    let s = s.trim_start_matches('-');
+    let (mantissa, exponent) = s.split_once(['e', 'E']).unwrap_or((s, "0"));
+    let (int, frac) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+
+    let digits = format!("{int}{frac}");
+    let trimmed = digits.trim_start_matches('0');
+    let point = int.len() as i64 - (digits.len() - trimmed.len()) as i64;
+    let trimmed = trimmed.trim_end_matches('0');
+
+    if trimmed.is_empty() {
+        return Some((String::new(), 0));
+    }
+    Some((
+        trimmed.to_owned(),
+        point.checked_add(exponent.parse().ok()?)?,
+    ))
+}
+
+impl FromStr for Number {
+    type Err = Error;
+
+    /// Parses a number with the same grammar as the JSON parser, rejecting
+    /// surrounding whitespace and trailing data.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_json(s.as_bytes())
+    }
+}
+
+/// Numbers that are not finite are written as `NaN`, `Infinity` or
+/// `-Infinity`, which is not valid JSON; use
+/// [`Value::to_string_with_options`](crate::Value::to_string_with_options) to
+/// reject or replace them.
 impl Display for Number {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.inner)
+        match self.inner {
+            N::PosInt(v) => write!(f, "{v}"),
+            N::NegInt(v) => write!(f, "{v}"),
+            // Large integral floats are written with an exponent, as the
+            // shortest decimal digits would otherwise be parsed back as a
+            // different integer.
+            N::Float(v) if v.fract() == 0.0 && v.abs() >= F64_EXACT_INT_LIMIT => {
+                write!(f, "{v:e}")
+            }
+            // Non-finite numbers are written as in JSON5.
+            N::Float(v) if v.is_nan() => write!(f, "NaN"),
+            N::Float(v) if v.is_infinite() => {
+                write!(f, "{}Infinity", if v < 0.0 { "-" } else { "" })
+            }
+            N::Float(v) => write!(f, "{v}"),
+        }
+    }
+}
+
+impl From<u64> for Number {
+    fn from(value: u64) -> Self {
+        Self {
+            inner: N::PosInt(value),
+        }
+    }
+}
+
+impl From<i64> for Number {
+    fn from(value: i64) -> Self {
+        match u64::try_from(value) {
+            Ok(v) => v.into(),
+            Err(_) => Self {
+                inner: N::NegInt(value),
+            },
+        }
     }
 }
 
+macro_rules! impl_from_int {
+    ($($t:ty => $via:ty),*) => {
+        $(
+            impl From<$t> for Number {
+                fn from(value: $t) -> Self {
+                    <$via>::try_from(value).unwrap().into()
+                }
+            }
+        )*
+    };
+}
+
+impl_from_int!(
+    u8 => u64, u16 => u64, u32 => u64, usize => u64,
+    i8 => i64, i16 => i64, i32 => i64, isize => i64
+);
+
 impl TryFrom<f64> for Number {
     type Error = ();
 
     fn try_from(value: f64) -> Result<Self, Self::Error> {
         if value.is_finite() {
-            Ok(Self { inner: value })
+            Ok(Self {
+                inner: N::Float(value),
+            })
         } else {
             Err(())
         }
@@ -125,41 +499,380 @@ impl TryFrom<serde_json::Number> for Number {
     type Error = crate::InvalidSerdeJsonNumber;
 
     fn try_from(value: serde_json::Number) -> Result<Self, Self::Error> {
-        let Some(value) = value.as_f64() else {
-            return Err(crate::InvalidSerdeJsonNumber(value));
-        };
-        Ok(value.try_into().unwrap())
+        if let Some(v) = value.as_u64() {
+            return Ok(v.into());
+        }
+        if let Some(v) = value.as_i64() {
+            return Ok(v.into());
+        }
+        match value.as_f64().map(Number::try_from) {
+            Some(Ok(v)) => Ok(v),
+            _ => Err(crate::InvalidSerdeJsonNumber(value)),
+        }
     }
 }
 
+/// Fails with the original number if it is not finite, which `serde_json`
+/// can't represent.
 #[cfg(feature = "serde_json")]
-impl From<Number> for serde_json::Number {
-    fn from(value: Number) -> Self {
-        serde_json::Number::from_f64(value.inner).unwrap()
+impl TryFrom<Number> for serde_json::Number {
+    type Error = Number;
+
+    fn try_from(value: Number) -> Result<Self, Self::Error> {
+        match value.inner {
+            N::PosInt(v) => Ok(v.into()),
+            N::NegInt(v) => Ok(v.into()),
+            N::Float(v) => serde_json::Number::from_f64(v).ok_or(value),
+        }
     }
 }
 
 #[cfg(test)]
 mod test {
+    use proptest::prelude::*;
+
     use super::*;
+    use crate::{options::Options, Value};
+
+    fn float(v: f64) -> Number {
+        Number::try_from(v).unwrap()
+    }
 
     #[test]
     fn test_parse_int() {
-        assert_eq!(Number::from_json(b"123"), Ok(Number { inner: 123.0 }));
+        assert_eq!(Number::from_json(b"123"), Ok(float(123.0)));
+        assert_eq!(Number::from_json(b"123").unwrap().as_u64(), Some(123));
+        assert_eq!(Number::from_json(b"-123").unwrap().as_i64(), Some(-123));
+    }
+
+    #[test]
+    fn test_parse_large_int() {
+        let n = Number::from_json(b"18446744073709551615").unwrap();
+        assert_eq!(n.as_u64(), Some(u64::MAX));
+        assert_eq!(n.to_string(), "18446744073709551615");
+
+        let n = Number::from_json(b"-9223372036854775808").unwrap();
+        assert_eq!(n.as_i64(), Some(i64::MIN));
+
+        let n = Number::from_json(b"18446744073709551616").unwrap();
+        assert!(n.is_f64());
+        assert_eq!(n, float(18446744073709551616.0));
+    }
+
+    #[test]
+    fn test_parse_negative_zero() {
+        let n = Number::from_json(b"-0").unwrap();
+        assert!(n.is_f64());
+        assert_eq!(n.to_string(), "-0");
+        assert_eq!(n, Number::from(0));
+    }
+
+    #[test]
+    fn test_large_integral_float_round_trip() {
+        let n = float(-4.133861130182398e16);
+        assert_eq!(n.to_string(), "-4.133861130182398e16");
+        assert_eq!(Number::from_json(n.to_string().as_bytes()), Ok(n));
+        assert_eq!(float(2.0).to_string(), "2");
+    }
+
+    #[test]
+    fn test_extreme_float_round_trip() {
+        for v in [
+            5e-324,
+            -5e-324,
+            f64::MIN_POSITIVE,
+            2.225073858507201e-308,
+            f64::MAX,
+            f64::MIN,
+            f64::EPSILON,
+        ] {
+            let s = float(v).to_string();
+            let n = Number::from_json(s.as_bytes()).unwrap();
+            assert_eq!(n.as_f64().to_bits(), v.to_bits(), "{s}");
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn test_float_to_string_and_back(
+            v in prop::num::f64::POSITIVE
+                | prop::num::f64::NEGATIVE
+                | prop::num::f64::NORMAL
+                | prop::num::f64::SUBNORMAL
+                | prop::num::f64::ZERO
+        ) {
+            let s = float(v).to_string();
+            let n = Number::from_json(s.as_bytes()).unwrap();
+            prop_assert_eq!(n.as_f64().to_bits(), v.to_bits(), "{}", s);
+        }
     }
 
     #[test]
     fn test_parse_fractional() {
-        assert_eq!(Number::from_json(b"1.23"), Ok(Number { inner: 1.23 }));
+        assert_eq!(Number::from_json(b"1.23"), Ok(float(1.23)));
     }
 
     #[test]
     fn test_parse_full() {
-        assert_eq!(Number::from_json(b"0.12e50"), Ok(Number { inner: 0.12e50 }));
+        assert_eq!(Number::from_json(b"0.12e50"), Ok(float(0.12e50)));
     }
 
     #[test]
     fn test_parse_inf() {
-        assert_eq!(Number::from_json(b"1e400"), Err(Error::InfiniteFloat));
+        assert_eq!(
+            Number::from_json(b"1e400"),
+            Err(Error::at(ErrorKind::InfiniteFloat, 5))
+        );
+    }
+
+    #[test]
+    fn test_from_str() {
+        let n: Number = "42".parse().unwrap();
+        assert!(n.is_u64());
+        assert_eq!(n.as_u64(), Some(42));
+
+        let n: Number = "4.2".parse().unwrap();
+        assert!(n.is_f64());
+        assert_eq!(n, float(4.2));
+
+        assert_eq!(
+            "4x".parse::<Number>(),
+            Err(Error::at(ErrorKind::TrailingData, 1))
+        );
+        assert_eq!(
+            " 4".parse::<Number>(),
+            Err(Error::at(ErrorKind::InvalidDigit(b' '), 1))
+        );
+    }
+
+    #[test]
+    fn test_int_float_comparison() {
+        assert_eq!(Number::from(1), float(1.0));
+        assert!(Number::from(1) < float(1.5));
+        assert!(Number::from(-2) < float(-1.5));
+        assert!(Number::from(-1) > float(-1.5));
+        assert!(Number::from(u64::MAX) < float(18446744073709551616.0));
+        assert!(Number::from(i64::MIN) < Number::from(0u64));
+        assert!(float(1e300) > Number::from(u64::MAX));
+        assert!(float(-1e300) < Number::from(i64::MIN));
+    }
+
+    #[test]
+    fn test_eq_exact() {
+        let one = Number::from_json(b"1").unwrap();
+        let one_float = Number::from_json(b"1.0").unwrap();
+        let one_exp = Number::from_json(b"1e0").unwrap();
+        assert!(one == one_float && one == one_exp);
+        assert!(!one.eq_exact(&one_float));
+        assert!(!one.eq_exact(&one_exp));
+        assert!(one_float.eq_exact(&one_exp));
+        assert!(one.eq_exact(&Number::from(1)));
+        assert!(float(0.0) == float(-0.0));
+        assert!(!float(0.0).eq_exact(&float(-0.0)));
+    }
+
+    #[test]
+    fn test_hash_agrees_with_eq() {
+        use std::hash::{BuildHasher, RandomState};
+
+        let state = RandomState::new();
+        assert_eq!(state.hash_one(Number::from(3)), state.hash_one(float(3.0)));
+        assert_eq!(state.hash_one(float(-0.0)), state.hash_one(float(0.0)));
+    }
+
+    #[cfg(feature = "serde_json")]
+    #[test]
+    fn test_serde_json_non_finite() {
+        let n = Number {
+            inner: N::Float(f64::NAN),
+        };
+        assert!(serde_json::Number::try_from(n.clone()).is_err());
+        assert_eq!(
+            serde_json::Value::try_from(Value::Number(n)).ok(),
+            Some(serde_json::Value::Null)
+        );
+        assert_eq!(
+            serde_json::Number::try_from(float(1.5)).ok(),
+            serde_json::Number::from_f64(1.5)
+        );
+    }
+
+    #[test]
+    fn test_allow_radix_integers() {
+        let options = Options {
+            allow_radix_integers: true,
+            ..Options::default()
+        };
+        let parse = |json: &[u8]| Value::from_json_with_options(json, &options);
+
+        assert_eq!(
+            parse(b"[0xFF, 0Xff, 0o17, -0x10, 10, 0.5, -0x8000000000000000]"),
+            Ok(vec![
+                255.into(),
+                255.into(),
+                15.into(),
+                (-16).into(),
+                10.into(),
+                Value::Number(float(0.5)),
+                i64::MIN.into(),
+            ]
+            .into())
+        );
+        assert_eq!(
+            parse(b"0x10000000000000000"),
+            Ok(Value::Number(float(18446744073709551616.0)))
+        );
+        assert_eq!(parse(b"0x"), Err(Error::at(ErrorKind::UnexpectedEof, 2)));
+        assert_eq!(
+            parse(b"0o8"),
+            Err(Error::at(ErrorKind::InvalidDigit(b'8'), 3))
+        );
+
+        assert_eq!(
+            Value::from_json(b"[0xFF]"),
+            Err(Error::at(ErrorKind::ExpectedCommaOrRightBracket(b'x'), 3))
+        );
+    }
+
+    #[test]
+    fn test_integer_overflow() {
+        let parse = |json: &[u8], integer_overflow| {
+            let options = Options {
+                integer_overflow,
+                allow_radix_integers: true,
+                ..Options::default()
+            };
+            Value::from_json_with_options(json, &options)
+        };
+        let over_u64 = b"18446744073709551616";
+        let under_i64 = b"-9223372036854775809";
+
+        assert_eq!(
+            parse(over_u64, OverflowPolicy::Float),
+            Ok(Value::Number(float(18446744073709551616.0)))
+        );
+        assert_eq!(
+            parse(over_u64, OverflowPolicy::Error),
+            Err(Error::at(ErrorKind::IntegerOverflow, 20))
+        );
+        assert_eq!(
+            parse(over_u64, OverflowPolicy::Saturate),
+            Ok(u64::MAX.into())
+        );
+
+        assert_eq!(
+            parse(under_i64, OverflowPolicy::Float),
+            Ok(Value::Number(float(-9223372036854775808.0)))
+        );
+        assert_eq!(
+            parse(under_i64, OverflowPolicy::Error),
+            Err(Error::at(ErrorKind::IntegerOverflow, 20))
+        );
+        assert_eq!(
+            parse(under_i64, OverflowPolicy::Saturate),
+            Ok(i64::MIN.into())
+        );
+
+        assert_eq!(
+            parse(b"0x10000000000000000", OverflowPolicy::Error),
+            Err(Error::at(ErrorKind::IntegerOverflow, 19))
+        );
+        assert_eq!(
+            parse(b"-0x8000000000000001", OverflowPolicy::Saturate),
+            Ok(i64::MIN.into())
+        );
+        for policy in [OverflowPolicy::Error, OverflowPolicy::Saturate] {
+            assert_eq!(parse(b"18446744073709551615", policy), Ok(u64::MAX.into()));
+            assert_eq!(parse(b"1e20", policy), Ok(Value::Number(float(1e20))));
+            assert_eq!(parse(b"-0", policy), Ok(Value::Number(float(-0.0))));
+        }
+    }
+
+    #[cfg(feature = "non_finite")]
+    #[test]
+    fn test_non_finite_constructors() {
+        use std::hash::{BuildHasher, RandomState};
+
+        use crate::ser::{NonFinitePolicy, WriteOptions};
+
+        assert!(Number::nan().as_f64().is_nan());
+        assert_eq!(Number::infinity().as_f64(), f64::INFINITY);
+        assert_eq!(Number::neg_infinity().as_f64(), f64::NEG_INFINITY);
+        assert_eq!(Number::from_f64_lossy(f64::INFINITY), Number::infinity());
+        assert_eq!(Number::from_f64_lossy(1.5), float(1.5));
+        assert_eq!(Number::try_from(f64::NAN), Err(()));
+
+        assert_eq!(Number::nan(), Number::from_f64_lossy(-f64::NAN));
+        let state = RandomState::new();
+        assert_eq!(
+            state.hash_one(Number::nan()),
+            state.hash_one(Number::from_f64_lossy(-f64::NAN))
+        );
+        let mut numbers = [
+            Number::nan(),
+            Number::infinity(),
+            Number::from(u64::MAX),
+            Number::neg_infinity(),
+            float(-1.5),
+        ];
+        numbers.sort();
+        assert_eq!(
+            numbers,
+            [
+                Number::neg_infinity(),
+                float(-1.5),
+                Number::from(u64::MAX),
+                Number::infinity(),
+                Number::nan(),
+            ]
+        );
+
+        assert_eq!(Number::nan().to_string(), "NaN");
+        assert_eq!(Number::neg_infinity().to_string(), "-Infinity");
+
+        let with_policy = |non_finite| WriteOptions {
+            non_finite,
+            ..WriteOptions::default()
+        };
+        let cases = [
+            (Number::nan(), "NaN"),
+            (Number::infinity(), "Infinity"),
+            (Number::neg_infinity(), "-Infinity"),
+        ];
+        for (number, literal) in cases {
+            let value = Value::Number(number);
+            assert!(value
+                .to_string_with_options(&with_policy(NonFinitePolicy::Error))
+                .is_err());
+            assert_eq!(
+                value
+                    .to_string_with_options(&with_policy(NonFinitePolicy::Null))
+                    .unwrap(),
+                "null"
+            );
+            assert_eq!(
+                value
+                    .to_string_with_options(&with_policy(NonFinitePolicy::Literal))
+                    .unwrap(),
+                literal
+            );
+        }
+    }
+
+    #[test]
+    fn test_custom_number_parser() {
+        fn round_to_cents(s: &str) -> Result<Number, ErrorKind> {
+            let v: f64 = s.parse().unwrap();
+            Number::try_from((v * 100.0).round() / 100.0).map_err(|()| ErrorKind::InfiniteFloat)
+        }
+
+        let options = Options {
+            number_parser: Some(round_to_cents),
+            ..Options::default()
+        };
+        assert_eq!(
+            Value::from_json_with_options(b"[1.234, 5.678, 9]", &options),
+            Ok(vec![float(1.23).into(), float(5.68).into(), float(9.0).into(),].into())
+        );
     }
 }
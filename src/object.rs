@@ -1,23 +1,42 @@
 use std::{
-    collections::{btree_map, BTreeMap},
-    fmt::Display,
+    cmp::Ordering,
+    collections::{btree_map, BTreeMap, HashMap},
+    fmt::{Debug, Display},
+    ops::RangeBounds,
 };
 
 use crate::{
-    error::Error,
+    error::{Error, ErrorKind},
     parser::Reader,
     read_value,
-    string::{read_string, JsonStr, JsonString},
+    schema::type_name,
+    skip_value,
+    string::{read_key, skip_string, JsonStr, JsonString},
     Value,
 };
 
 type Map = BTreeMap<JsonString, Value>;
 
-#[derive(Debug, Default, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Default, Clone, PartialEq, Eq, Hash)]
 pub struct Object {
     inner: Map,
 }
 
+/// Objects are ordered lexicographically by their entries in key order,
+/// independent of the order the keys were inserted in.
+impl Ord for Object {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // The map iterates in key order.
+        self.inner.iter().cmp(other.inner.iter())
+    }
+}
+
+impl PartialOrd for Object {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
 impl Object {
     pub fn new() -> Self {
         Self::default()
@@ -27,6 +46,24 @@ impl Object {
         Reader::read_all(bytes, read_object)
     }
 
+    /// Parses an object into its members in document order, keeping every
+    /// member with a duplicate key instead of only the last.
+    ///
+    /// Only the outer object keeps its duplicates, nested objects are parsed
+    /// as usual.
+    pub fn from_json_pairs(bytes: &[u8]) -> Result<Vec<(JsonString, Value)>, Error> {
+        Reader::read_all(bytes, |reader| {
+            let mut pairs = Vec::new();
+            read_members(reader, read_checked_key, |reader, key| {
+                let value = read_value(reader)?;
+                reader.allocate(size_of::<(JsonString, Value)>())?;
+                pairs.push((key, value));
+                Ok(())
+            })?;
+            Ok(pairs)
+        })
+    }
+
     pub fn is_empty(&self) -> bool {
         self.inner.is_empty()
     }
@@ -39,6 +76,14 @@ impl Object {
         self.inner
     }
 
+    pub fn into_keys(self) -> btree_map::IntoKeys<JsonString, Value> {
+        self.inner.into_keys()
+    }
+
+    pub fn into_values(self) -> btree_map::IntoValues<JsonString, Value> {
+        self.inner.into_values()
+    }
+
     pub fn as_inner(&self) -> &Map {
         &self.inner
     }
@@ -63,6 +108,20 @@ impl Object {
         self.inner.get_mut(key.into())
     }
 
+    /// Looks up `key`, distinguishing a missing key from one explicitly set
+    /// to `null`.
+    pub fn get_defined<'a, Q>(&self, key: &'a Q) -> Presence<'_>
+    where
+        Q: ?Sized,
+        &'a Q: Into<&'a JsonStr>,
+    {
+        match self.inner.get(key.into()) {
+            None => Presence::Absent,
+            Some(Value::Null) => Presence::Null,
+            Some(v) => Presence::Present(v),
+        }
+    }
+
     pub fn contains_key<'a, Q>(&self, key: &'a Q) -> bool
     where
         Q: ?Sized,
@@ -87,12 +146,79 @@ impl Object {
         self.inner.clear()
     }
 
+    /// Removes all entries, returning them in key order.
+    pub fn drain(&mut self) -> btree_map::IntoIter<JsonString, Value> {
+        std::mem::take(&mut self.inner).into_iter()
+    }
+
     pub fn entry(&mut self, key: JsonString) -> Entry<'_> {
         match self.inner.entry(key) {
             btree_map::Entry::Vacant(entry) => Entry::Vacant(VacantEntry(entry)),
             btree_map::Entry::Occupied(entry) => Entry::Occupied(OccupiedEntry(entry)),
         }
     }
+
+    /// Returns the entry with the smallest key.
+    pub fn first_key_value(&self) -> Option<(&JsonString, &Value)> {
+        self.inner.first_key_value()
+    }
+
+    /// Returns the entry with the largest key.
+    pub fn last_key_value(&self) -> Option<(&JsonString, &Value)> {
+        self.inner.last_key_value()
+    }
+
+    /// Iterates over the entries with keys in `range`, in key order.
+    pub fn range<R>(&self, range: R) -> btree_map::Range<'_, JsonString, Value>
+    where
+        R: RangeBounds<JsonString>,
+    {
+        self.inner.range(range)
+    }
+
+    /// Returns the entry with the maximum value according to `cmp`.
+    ///
+    /// If several entries are equally maximum, the last one is returned.
+    pub fn max_by_value<F>(&self, mut cmp: F) -> Option<(&JsonString, &Value)>
+    where
+        F: FnMut(&Value, &Value) -> Ordering,
+    {
+        self.inner.iter().max_by(|(_, a), (_, b)| cmp(a, b))
+    }
+
+    /// Returns the entry with the minimum value according to `cmp`.
+    ///
+    /// If several entries are equally minimum, the first one is returned.
+    pub fn min_by_value<F>(&self, mut cmp: F) -> Option<(&JsonString, &Value)>
+    where
+        F: FnMut(&Value, &Value) -> Ordering,
+    {
+        self.inner.iter().min_by(|(_, a), (_, b)| cmp(a, b))
+    }
+
+    /// Iterates over the entries ordered by their values according to `cmp`,
+    /// with equal values in key order.
+    ///
+    /// This collects and sorts the entries, so it allocates and takes
+    /// O(n log n) time.
+    pub fn iter_by_value<F>(&self, mut cmp: F) -> impl Iterator<Item = (&JsonString, &Value)>
+    where
+        F: FnMut(&Value, &Value) -> Ordering,
+    {
+        let mut entries: Vec<_> = self.inner.iter().collect();
+        entries.sort_by(|(_, a), (_, b)| cmp(a, b));
+        entries.into_iter()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Presence<'a> {
+    /// The key is not in the object.
+    Absent,
+    /// The key is in the object with a `null` value.
+    Null,
+    /// The key is in the object with a non-`null` value.
+    Present(&'a Value),
 }
 
 pub enum Entry<'a> {
@@ -146,6 +272,29 @@ impl<'a> Entry<'a> {
     {
         Self::from_inner_entry(self.into_inner_entry().and_modify(f))
     }
+
+    /// Returns the object in the entry, inserting an empty object if the
+    /// entry is vacant or `null`, or fails if it holds another type.
+    pub fn or_try_insert_object(self) -> Result<&'a mut Object, WrongType> {
+        let value = self.or_insert(Value::Null);
+        if let Value::Null = value {
+            *value = Value::Object(Object::new());
+        }
+        match value {
+            Value::Object(obj) => Ok(obj),
+            v => Err(WrongType {
+                expected: "object",
+                found: type_name(v),
+            }),
+        }
+    }
+}
+
+/// The value in an [`Entry`] is not of the expected type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WrongType {
+    pub expected: &'static str,
+    pub found: &'static str,
 }
 
 pub struct VacantEntry<'a>(btree_map::VacantEntry<'a, JsonString, Value>);
@@ -196,43 +345,125 @@ impl<'a> OccupiedEntry<'a> {
     }
 }
 
-pub(crate) fn read_object(reader: &mut Reader) -> Result<Object, Error> {
+pub(crate) fn read_object(reader: &mut Reader) -> Result<Object, ErrorKind> {
+    #[cfg(feature = "tracing")]
+    tracing::trace!(offset = reader.offset(), "reading object");
+
+    let mut inner = Map::new();
+    read_members(reader, read_checked_key, |reader, key| {
+        let value = read_value(reader)?;
+        reader.allocate(size_of::<(JsonString, Value)>())?;
+        inner.insert(key, value);
+        Ok(())
+    })?;
+    Ok(Object { inner })
+}
+
+/// Reads an object, reading each key with `read_key` and then calling
+/// `read_member` with it to read the value. Members are visited in document
+/// order, including duplicate keys.
+///
+/// All the ways of parsing an object go through this, so they accept the same
+/// separators and trailing commas.
+pub(crate) fn read_members<K>(
+    reader: &mut Reader,
+    read_key: fn(&mut Reader) -> Result<K, ErrorKind>,
+    mut read_member: impl FnMut(&mut Reader, K) -> Result<(), ErrorKind>,
+) -> Result<(), ErrorKind> {
     match reader.read_byte()? {
         b'{' => {}
         b => {
-            return Err(Error::ExpectedLeftBrace(b));
+            return Err(ErrorKind::ExpectedLeftBrace(b));
         }
     }
     reader.skip_whitespace();
     if reader.peek_byte() == Some(b'}') {
         reader.read_byte()?;
-        return Ok(Object::default());
+        return Ok(());
     }
-    let mut inner = Map::new();
     loop {
-        let key = read_string(reader)?;
+        let key = read_key(reader)?;
 
         reader.skip_whitespace();
 
         match reader.read_byte()? {
             b':' => {}
-            b => return Err(Error::ExpectedColon(b)),
+            b => return Err(ErrorKind::ExpectedColon(b)),
         }
 
-        let value = read_value(reader)?;
-
-        inner.insert(key, value);
+        reader.skip_whitespace();
+        read_member(reader, key)?;
 
         reader.skip_whitespace();
         match reader.read_byte()? {
+            b',' if reader.skip_trailing_comma(b'}') => break,
             b',' => {}
             b'}' => break,
-            b => return Err(Error::ExpectedCommaOrRightBrace(b)),
+            b => return Err(ErrorKind::ExpectedCommaOrRightBrace(b)),
         }
 
         reader.skip_whitespace();
     }
-    Ok(Object { inner })
+    Ok(())
+}
+
+/// Reads an object key with [`read_key`], rejecting those in
+/// [`Options::forbidden_keys`].
+///
+/// [`Options::forbidden_keys`]: crate::Options::forbidden_keys
+pub(crate) fn read_checked_key(reader: &mut Reader) -> Result<JsonString, ErrorKind> {
+    let key = read_key(reader)?;
+    if key
+        .as_str()
+        .is_some_and(|k| reader.options().forbidden_keys.contains(&k))
+    {
+        return Err(ErrorKind::ForbiddenKey(key));
+    }
+    Ok(key)
+}
+
+/// Validates an object like [`read_object`] without building it, returning
+/// its number of members.
+pub(crate) fn skip_object(reader: &mut Reader) -> Result<usize, ErrorKind> {
+    let mut len = 0;
+    read_members(reader, skip_key, |reader, ()| {
+        skip_value(reader)?;
+        len += 1;
+        Ok(())
+    })?;
+    Ok(len)
+}
+
+/// Validates an object key like [`read_checked_key`], without building it if
+/// it's quoted.
+///
+/// Keys are still built when [`Options::max_key_len`] is set, as the limit
+/// applies to the unescaped key. That is cheap, since reading stops at the
+/// limit. They are also built when [`Options::forbidden_keys`] is non-empty,
+/// to be compared.
+///
+/// [`Options::max_key_len`]: crate::Options::max_key_len
+/// [`Options::forbidden_keys`]: crate::Options::forbidden_keys
+fn skip_key(reader: &mut Reader) -> Result<(), ErrorKind> {
+    let options = reader.options();
+    if reader.peek_byte() == Some(b'"')
+        && options.max_key_len.is_none()
+        && options.forbidden_keys.is_empty()
+    {
+        skip_string(reader)
+    } else {
+        read_checked_key(reader).map(drop)
+    }
+}
+
+impl Debug for Object {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut map = f.debug_map();
+        for (k, v) in &self.inner {
+            map.entry(&format_args!("{k}"), v);
+        }
+        map.finish()
+    }
 }
 
 impl Display for Object {
@@ -263,6 +494,32 @@ impl IntoIterator for Object {
     }
 }
 
+impl FromIterator<(JsonString, Value)> for Object {
+    fn from_iter<T: IntoIterator<Item = (JsonString, Value)>>(iter: T) -> Self {
+        Self {
+            inner: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl From<HashMap<String, Value>> for Object {
+    fn from(value: HashMap<String, Value>) -> Self {
+        value.into_iter().map(|(k, v)| (k.into(), v)).collect()
+    }
+}
+
+/// Fails with the first key that contains a lone surrogate.
+impl TryFrom<Object> for HashMap<String, Value> {
+    type Error = JsonString;
+
+    fn try_from(value: Object) -> Result<Self, Self::Error> {
+        value
+            .into_iter()
+            .map(|(k, v)| Ok((k.into_string()?, v)))
+            .collect()
+    }
+}
+
 #[cfg(feature = "serde_json")]
 impl TryFrom<serde_json::Map<String, serde_json::Value>> for Object {
     type Error = crate::InvalidSerdeJsonNumber;
@@ -281,21 +538,19 @@ impl TryFrom<Object> for serde_json::Map<String, serde_json::Value> {
     type Error = crate::InvalidUnicodeString;
 
     fn try_from(value: Object) -> Result<Self, Self::Error> {
-        value
-            .into_iter()
-            .map(|(k, v)| {
-                Ok((
-                    k.into_string().map_err(crate::InvalidUnicodeString)?,
-                    v.try_into()?,
-                ))
-            })
-            .collect::<Result<serde_json::Map<String, serde_json::Value>, _>>()
+        match serde_json::Value::try_from(Value::Object(value))? {
+            serde_json::Value::Object(map) => Ok(map),
+            _ => unreachable!(),
+        }
     }
 }
 
 #[cfg(test)]
 mod test {
+    use std::borrow::Borrow;
+
     use super::*;
+    use crate::{options::Options, Json5};
 
     #[test]
     fn test_parse_obj_with_whitespace() {
@@ -311,4 +566,314 @@ mod test {
         obj.insert(JsonString::from("abc"), Value::Null);
         assert_eq!(obj.get("abc"), Some(&Value::Null));
     }
+
+    #[test]
+    fn test_borrowed_keys() {
+        let key = JsonString::from_ill_formed_utf16(&[0xd800]);
+        let mut obj = Object::new();
+        obj.insert(key.clone(), Value::Null);
+        assert!(obj.contains_key(&key));
+        assert!(obj.contains_key(Borrow::<JsonStr>::borrow(&key)));
+        assert_eq!(obj.get(&key), Some(&Value::Null));
+        assert_eq!(obj.get("a"), None);
+        assert_eq!(obj.get(&"a".to_owned()), None);
+        assert_eq!(obj.remove(&key), Some(Value::Null));
+    }
+
+    #[test]
+    fn test_max_min_by_value() {
+        let o = Object::from_json(br#"{"alice": 3, "bob": 7, "carol": 5}"#).unwrap();
+        let (key, value) = o.max_by_value(Value::cmp).unwrap();
+        assert_eq!(key, &JsonString::from("bob"));
+        assert_eq!(value, &Value::try_from(7.0).unwrap());
+
+        let (key, _) = o.min_by_value(Value::cmp).unwrap();
+        assert_eq!(key, &JsonString::from("alice"));
+
+        assert_eq!(Object::new().max_by_value(Value::cmp), None);
+    }
+
+    #[test]
+    fn test_first_last_key_value() {
+        let obj = Object::from_json(br#"{"b": 2, "c": 3, "a": 1}"#).unwrap();
+        assert_eq!(obj.first_key_value(), Some((&"a".into(), &1.into())));
+        assert_eq!(obj.last_key_value(), Some((&"c".into(), &3.into())));
+        assert_eq!(Object::new().first_key_value(), None);
+    }
+
+    #[test]
+    fn test_range() {
+        let obj = Object::from_json(br#"{"a": 1, "m": 2, "mo": 3, "y": 4, "z": 5}"#).unwrap();
+        let keys: Vec<_> = obj
+            .range(JsonString::from("m")..JsonString::from("z"))
+            .map(|(k, _)| k.as_str().unwrap())
+            .collect();
+        assert_eq!(keys, ["m", "mo", "y"]);
+    }
+
+    #[test]
+    fn test_iter_by_value() {
+        let obj = Object::from_json(br#"{"ann": 3, "bob": 10, "cat": 7, "dan": 10}"#).unwrap();
+        let ranking: Vec<_> = obj
+            .iter_by_value(|a, b| b.cmp(a))
+            .map(|(k, _)| k.as_str().unwrap())
+            .collect();
+        assert_eq!(ranking, ["bob", "dan", "cat", "ann"]);
+    }
+
+    #[test]
+    fn test_hash_map() {
+        let obj = Object::from_json(br#"{"a": 1, "b": [true]}"#).unwrap();
+        let map = HashMap::try_from(obj.clone()).unwrap();
+        assert_eq!(map.len(), 2);
+        assert_eq!(map["b"], Value::from_json(b"[true]").unwrap());
+        assert_eq!(Object::from(map), obj);
+
+        let obj = Object::from_json(br#"{"a": 1, "\ud800": 2}"#).unwrap();
+        assert_eq!(
+            HashMap::try_from(obj),
+            Err(JsonString::from_ill_formed_utf16(&[0xd800]))
+        );
+    }
+
+    #[test]
+    fn test_drain() {
+        let mut obj = Object::from_json(br#"{"b": 2, "a": 1}"#).unwrap();
+        let entries: Vec<_> = obj.drain().collect();
+        assert_eq!(
+            entries,
+            [("a".into(), 1.into()), ("b".into(), Value::from(2))]
+        );
+        assert!(obj.is_empty());
+    }
+
+    #[test]
+    fn test_ord() {
+        let mut a = Object::new();
+        a.insert("x".into(), 1.into());
+        a.insert("y".into(), 2.into());
+        let mut b = Object::new();
+        b.insert("y".into(), 2.into());
+        b.insert("x".into(), 1.into());
+        assert_eq!(a.cmp(&b), Ordering::Equal);
+
+        b.insert("x".into(), 0.into());
+        assert_eq!(a.cmp(&b), Ordering::Greater);
+        assert!(Object::new() < a);
+    }
+
+    #[test]
+    fn test_get_defined() {
+        let o = Object::from_json(br#"{"a": null, "b": 1}"#).unwrap();
+        assert_eq!(o.get_defined("a"), Presence::Null);
+        assert_eq!(o.get_defined("b"), Presence::Present(o.get("b").unwrap()));
+        assert_eq!(o.get_defined("c"), Presence::Absent);
+    }
+
+    #[test]
+    fn test_into_keys_and_values() {
+        let o = Object::from_json(br#"{"b": 2, "a": 1}"#).unwrap();
+        assert_eq!(
+            o.clone().into_keys().collect::<Vec<_>>(),
+            vec![JsonString::from("a"), JsonString::from("b")]
+        );
+        assert_eq!(
+            o.into_values().collect::<Vec<_>>(),
+            vec![Value::try_from(1.0).unwrap(), Value::try_from(2.0).unwrap()]
+        );
+    }
+
+    #[test]
+    fn test_control_character_in_key() {
+        let json = b"{\"a\x01\": 1}";
+        assert_eq!(
+            Object::from_json(json),
+            Err(Error::at(ErrorKind::InvalidControlCharacter(0x01), 3))
+        );
+        assert_eq!(
+            Value::from_json(b"[{\"ok\": 1, \"\n\": 2}]"),
+            Err(Error::at(ErrorKind::InvalidControlCharacter(b'\n'), 12))
+        );
+        // Skipped keys are validated too.
+        assert_eq!(
+            Value::probe(json),
+            Err(Error::at(ErrorKind::InvalidControlCharacter(0x01), 3))
+        );
+        assert_eq!(
+            Value::extract_pointer(json, "/b"),
+            Err(Error::at(ErrorKind::InvalidControlCharacter(0x01), 3))
+        );
+    }
+
+    #[test]
+    fn test_max_alloc_bytes() {
+        let json = br#"{"key": ["abcdefghij", "abcdefghij"]}"#;
+        let member = size_of::<(JsonString, Value)>();
+        let element = size_of::<Value>();
+        let size = 3 + 2 * (10 + element) + member;
+
+        let options = Options {
+            max_alloc_bytes: Some(size),
+            ..Options::default()
+        };
+        assert!(Value::from_json_with_options(json, &options).is_ok());
+
+        let options = Options {
+            max_alloc_bytes: Some(size - 1),
+            ..Options::default()
+        };
+        assert_eq!(
+            Value::from_json_with_options(json, &options),
+            Err(Error::at(ErrorKind::AllocationLimitExceeded, 36))
+        );
+
+        // Unquoted strings are counted like quoted ones.
+        let json = br#"{"key": [abcdefghij, abcdefghij]}"#;
+        let options = Options {
+            allow_unquoted_values: true,
+            max_alloc_bytes: Some(size),
+            ..Options::default()
+        };
+        assert!(Value::from_json_with_options(json, &options).is_ok());
+
+        let options = Options {
+            max_alloc_bytes: Some(size - 1),
+            ..options
+        };
+        assert_eq!(
+            Value::from_json_with_options(json, &options),
+            Err(Error::at(ErrorKind::AllocationLimitExceeded, 32))
+        );
+    }
+
+    #[test]
+    fn test_forbidden_keys() {
+        let options = Options {
+            forbidden_keys: &["__proto__", "constructor"],
+            ..Options::default()
+        };
+        let json = br#"{"a": {"__proto__": {"admin": true}}}"#;
+        assert_eq!(
+            Value::from_json_with_options(json, &options),
+            Err(Error::at(ErrorKind::ForbiddenKey("__proto__".into()), 18))
+        );
+        assert!(Value::from_json(json).is_ok());
+        assert!(Value::from_json_with_options(br#"{"proto": 1}"#, &options).is_ok());
+
+        // The keys can be built at runtime, e.g. from a config file.
+        let config = String::from("secret,token");
+        let keys: Vec<&str> = config.split(',').collect();
+        let options = Options {
+            forbidden_keys: &keys,
+            ..Options::default()
+        };
+        assert_eq!(
+            Value::from_json_with_options(br#"{"token": 1}"#, &options),
+            Err(Error::at(ErrorKind::ForbiddenKey("token".into()), 8))
+        );
+    }
+
+    #[test]
+    fn test_or_try_insert_object() {
+        let mut obj = Object::from_json(br#"{"a": {"b": 1}, "c": 2, "d": null}"#).unwrap();
+        obj.entry("a".into())
+            .or_try_insert_object()
+            .unwrap()
+            .insert("x".into(), 1.into());
+        obj.entry("new".into())
+            .or_try_insert_object()
+            .unwrap()
+            .insert("y".into(), 2.into());
+        assert_eq!(
+            obj.entry("d".into()).or_try_insert_object(),
+            Ok(&mut Object::new())
+        );
+        assert_eq!(
+            obj.entry("c".into()).or_try_insert_object(),
+            Err(WrongType {
+                expected: "object",
+                found: "number"
+            })
+        );
+        assert_eq!(
+            obj,
+            Object::from_json(br#"{"a": {"b": 1, "x": 1}, "c": 2, "d": {}, "new": {"y": 2}}"#)
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_from_json_pairs() {
+        assert_eq!(
+            Object::from_json_pairs(br#"{"a":1,"a":2,"b":3}"#),
+            Ok(vec![
+                ("a".into(), 1.into()),
+                ("a".into(), 2.into()),
+                ("b".into(), 3.into())
+            ])
+        );
+        assert_eq!(
+            Object::from_json(br#"{"a":1,"a":2,"b":3}"#),
+            Object::from_json(br#"{"a":2,"b":3}"#)
+        );
+        assert_eq!(Object::from_json_pairs(b"{}"), Ok(vec![]));
+        assert_eq!(
+            Object::from_json_pairs(b"[]"),
+            Err(Error::at(ErrorKind::ExpectedLeftBrace(b'['), 1))
+        );
+    }
+
+    #[test]
+    fn test_max_key_len() {
+        let options = Options {
+            max_key_len: Some(3),
+            ..Options::default()
+        };
+        assert_eq!(
+            Value::from_json_with_options(br#"{"abc": "a value longer than the limit"}"#, &options),
+            Value::from_json(br#"{"abc": "a value longer than the limit"}"#)
+        );
+        assert_eq!(
+            Value::from_json_with_options(br#"{"a": {"abcd": 1}}"#, &options),
+            Err(Error::at(ErrorKind::KeyTooLong, 12))
+        );
+        let options = Options {
+            dedup_scalars: true,
+            ..options
+        };
+        assert_eq!(
+            crate::SharedValue::from_json_with_options(br#"{"abcd": 1}"#, &options),
+            Err(Error::at(ErrorKind::KeyTooLong, 6))
+        );
+    }
+
+    #[test]
+    fn test_max_key_len_stops_early() {
+        let options = Options {
+            max_key_len: Some(3),
+            dialect: Some(&Json5),
+            ..Options::default()
+        };
+        let long_key = format!(r#"{{"{}": 1}}"#, "a".repeat(1_000_000));
+        assert_eq!(
+            Value::from_json_with_options(long_key.as_bytes(), &options),
+            Err(Error::at(ErrorKind::KeyTooLong, 6))
+        );
+        assert_eq!(
+            Value::from_json_with_options(br#"{"\u00e9\u00e9": 1}"#, &options),
+            Err(Error::at(ErrorKind::KeyTooLong, 14))
+        );
+        assert_eq!(
+            Value::from_json_with_options(b"{abcd: 1}", &options),
+            Err(Error::at(ErrorKind::KeyTooLong, 4))
+        );
+        assert_eq!(
+            Value::probe_with_options(long_key.as_bytes(), &options),
+            Err(Error::at(ErrorKind::KeyTooLong, 6))
+        );
+        assert_eq!(
+            Value::extract_pointer_with_options(br#"{"a": 1, "bcde": 2}"#, "/a", &options),
+            Err(Error::at(ErrorKind::KeyTooLong, 14))
+        );
+    }
 }
@@ -0,0 +1,136 @@
+use crate::{dialect::Dialect, error::ErrorKind, number::Number, pool::SharedStringPool, Value};
+
+pub type NumberParser = fn(&str) -> Result<Number, ErrorKind>;
+
+/// What to do with integer literals that don't fit in a `u64` or `i64`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OverflowPolicy {
+    /// Parse them as the nearest float, losing precision.
+    #[default]
+    Float,
+    /// Fail with [`ErrorKind::IntegerOverflow`].
+    Error,
+    /// Clamp them to `u64::MAX` or `i64::MIN`.
+    Saturate,
+}
+
+/// Options controlling how JSON is parsed.
+///
+/// The default options parse strict JSON without any limits.
+#[derive(Debug, Clone)]
+pub struct Options<'a> {
+    /// Maximum number of escape sequences allowed in a single string.
+    pub max_escapes_per_string: Option<usize>,
+    /// Fail with [`ErrorKind::LoneSurrogate`] on strings with a `\u` escape
+    /// for a surrogate that isn't part of a pair, e.g. `"\ud800"`, instead
+    /// of keeping it as WTF-8.
+    pub reject_lone_surrogates: bool,
+    /// Custom parser for number tokens.
+    ///
+    /// It is called with the raw token after it has been validated against
+    /// the JSON number grammar.
+    pub number_parser: Option<NumberParser>,
+    /// Function applied to every scalar value as it's read, e.g. to trim
+    /// strings. It's applied to the leaves before they are inserted into
+    /// their array or object, and not to object keys.
+    pub value_transform: Option<fn(Value) -> Value>,
+    /// Object keys that are rejected with [`ErrorKind::ForbiddenKey`], e.g.
+    /// `__proto__`.
+    pub forbidden_keys: &'a [&'a str],
+    /// Maximum length in bytes of an object key, after unescaping. Longer
+    /// keys are rejected with [`ErrorKind::KeyTooLong`]. String values are
+    /// not limited by this.
+    pub max_key_len: Option<usize>,
+    /// The JSON variant to accept, [`Strict`] if not set.
+    ///
+    /// [`Strict`]: crate::Strict
+    pub dialect: Option<&'a dyn Dialect>,
+    /// Bytes treated as insignificant whitespace in addition to the four
+    /// allowed by RFC 8259, or those of the dialect, e.g. `b"\x0b\x0c"` for
+    /// vertical tab and form feed.
+    pub extra_whitespace: &'a [u8],
+    /// Skip a leading UTF-8 byte order mark.
+    pub allow_bom: bool,
+    /// Skip a leading `)]}'` prefix, as sent by some APIs to prevent
+    /// cross-site script inclusion, and the whitespace following it.
+    pub strip_xssi_prefix: bool,
+    /// Stop after the first value instead of failing with
+    /// [`ErrorKind::TrailingData`] when anything but whitespace follows it.
+    pub allow_trailing_data: bool,
+    /// Accept unquoted strings as values, e.g. `{"mode": fast}`.
+    ///
+    /// A bare word starts with an ASCII letter, `_` or `$`, and runs until a
+    /// `,`, `]`, `}`, whitespace or the end of the input. `null`, `true` and
+    /// `false` keep their usual meaning.
+    pub allow_unquoted_values: bool,
+    /// Accept `null`, `true` and `false` in any case, e.g. `NULL` or `True`.
+    pub case_insensitive_literals: bool,
+    /// Accept hexadecimal (`0x1F`) and octal (`0o17`) integers, optionally
+    /// preceded by `-`.
+    ///
+    /// Like decimal integers, integers that don't fit in a `u64` or `i64` are
+    /// handled according to `integer_overflow`.
+    pub allow_radix_integers: bool,
+    /// How to parse integers that don't fit in a `u64` or `i64`. Numbers with
+    /// a fraction or exponent are always floats.
+    ///
+    /// This doesn't apply to numbers read by a custom `number_parser`.
+    pub integer_overflow: OverflowPolicy,
+    /// Sort the elements of every array, for comparing arrays that represent
+    /// sets. This loses the original element order.
+    ///
+    /// Object keys are always sorted, so this makes documents that only
+    /// differ in ordering parse to equal values.
+    pub sort_arrays: bool,
+    /// Fail with [`ErrorKind::AllocationLimitExceeded`] once the parsed value
+    /// takes up more than this many bytes.
+    ///
+    /// The size is approximated as the length of every string plus the size
+    /// of every array element and object member, ignoring allocator and map
+    /// overhead.
+    pub max_alloc_bytes: Option<usize>,
+    /// Make [`SharedValue::from_json_with_options`] intern strings while
+    /// parsing, instead of parsing a [`Value`] first. This lowers the peak
+    /// memory use for documents with many repeated strings.
+    ///
+    /// [`SharedValue::from_json_with_options`]: crate::SharedValue::from_json_with_options
+    /// [`Value`]: crate::Value
+    pub dedup_scalars: bool,
+    /// Pool used by [`SharedValue::from_json_with_options`] to share strings
+    /// between documents. Ignored when parsing a [`Value`].
+    ///
+    /// [`SharedValue::from_json_with_options`]: crate::SharedValue::from_json_with_options
+    /// [`Value`]: crate::Value
+    pub string_pool: Option<SharedStringPool>,
+}
+
+impl Options<'static> {
+    /// The default options, usable in `const` and `static` items.
+    pub const DEFAULT: Self = Self {
+        max_escapes_per_string: None,
+        reject_lone_surrogates: false,
+        number_parser: None,
+        value_transform: None,
+        forbidden_keys: &[],
+        max_key_len: None,
+        dialect: None,
+        extra_whitespace: &[],
+        allow_bom: false,
+        strip_xssi_prefix: false,
+        allow_trailing_data: false,
+        allow_unquoted_values: false,
+        case_insensitive_literals: false,
+        allow_radix_integers: false,
+        integer_overflow: OverflowPolicy::Float,
+        sort_arrays: false,
+        max_alloc_bytes: None,
+        dedup_scalars: false,
+        string_pool: None,
+    };
+}
+
+impl Default for Options<'_> {
+    fn default() -> Self {
+        Options::DEFAULT
+    }
+}
@@ -1,37 +1,112 @@
-use crate::error::Error;
+use crate::{
+    dialect::{Dialect, Strict},
+    error::{Error, ErrorKind, Warning},
+    options::Options,
+    read_value, Value,
+};
+
+/// The UTF-8 encoded byte order mark.
+pub(crate) const BOM: &[u8] = b"\xEF\xBB\xBF";
+
+const XSSI_PREFIX: &[u8] = b")]}'";
 
 pub(crate) struct Reader<'a> {
     bytes: &'a [u8],
+    len: usize,
+    options: &'a Options<'a>,
+    /// Collected warnings, if requested.
+    pub(crate) warnings: Option<Vec<Warning>>,
+    /// Approximate number of bytes allocated for the parsed value so far.
+    allocated: usize,
 }
 
 impl<'a> Reader<'a> {
     pub(crate) fn read_all<T>(
+        bytes: &[u8],
+        f: impl FnOnce(&mut Reader) -> Result<T, ErrorKind>,
+    ) -> Result<T, Error> {
+        Reader::read_all_with_options(bytes, &Options::default(), f)
+    }
+
+    pub(crate) fn read_all_with_options<T>(
         bytes: &'a [u8],
-        f: impl FnOnce(&mut Reader) -> Result<T, Error>,
+        options: &'a Options<'a>,
+        f: impl FnOnce(&mut Reader) -> Result<T, ErrorKind>,
     ) -> Result<T, Error> {
-        let mut parser = Reader::new(bytes);
-        let v = f(&mut parser)?;
-        if !parser.at_end() {
-            return Err(Error::TrailingData);
+        let mut parser = Reader::new(bytes, options);
+        parser.skip_prefixes();
+        let v = f(&mut parser).map_err(|kind| Error::at(kind, parser.offset()))?;
+        if !options.allow_trailing_data && !parser.at_end() {
+            return Err(Error::at(ErrorKind::TrailingData, parser.offset()));
         }
         Ok(v)
     }
 
-    pub(crate) fn new(bytes: &'a [u8]) -> Self {
-        Self { bytes }
+    /// Skips a byte order mark and XSSI prefix at the start of the input, if
+    /// the options allow them.
+    fn skip_prefixes(&mut self) {
+        if self.options.allow_bom {
+            if let Some(rest) = self.bytes.strip_prefix(BOM) {
+                self.bytes = rest;
+            }
+        }
+        if self.options.strip_xssi_prefix {
+            if let Some(rest) = self.bytes.strip_prefix(XSSI_PREFIX) {
+                self.bytes = rest;
+                self.skip_whitespace();
+            }
+        }
+    }
+
+    pub(crate) fn new(bytes: &'a [u8], options: &'a Options<'a>) -> Self {
+        Self {
+            bytes,
+            len: bytes.len(),
+            options,
+            warnings: None,
+            allocated: 0,
+        }
+    }
+
+    pub(crate) fn options(&self) -> &'a Options<'a> {
+        self.options
+    }
+
+    /// Accounts for `bytes` more bytes allocated, checking
+    /// [`Options::max_alloc_bytes`].
+    pub(crate) fn allocate(&mut self, bytes: usize) -> Result<(), ErrorKind> {
+        self.allocated = self.allocated.saturating_add(bytes);
+        if self
+            .options
+            .max_alloc_bytes
+            .is_some_and(|max| self.allocated > max)
+        {
+            return Err(ErrorKind::AllocationLimitExceeded);
+        }
+        Ok(())
+    }
+
+    /// Number of bytes consumed so far.
+    pub(crate) fn offset(&self) -> usize {
+        self.len - self.bytes.len()
+    }
+
+    /// The bytes that haven't been consumed yet.
+    pub(crate) fn remaining(&self) -> &'a [u8] {
+        self.bytes
     }
 
     pub(crate) fn at_end(&self) -> bool {
         self.bytes.is_empty()
     }
 
-    pub(crate) fn read_byte(&mut self) -> Result<u8, Error> {
+    pub(crate) fn read_byte(&mut self) -> Result<u8, ErrorKind> {
         match self.bytes.split_first() {
             Some((b, rest)) => {
                 self.bytes = rest;
                 Ok(*b)
             }
-            None => Err(Error::UnexpectedEof),
+            None => Err(ErrorKind::UnexpectedEof),
         }
     }
 
@@ -39,20 +114,20 @@ impl<'a> Reader<'a> {
         self.bytes.first().copied()
     }
 
-    pub(crate) fn read_bytes<const N: usize>(&mut self) -> Result<&'a [u8; N], Error> {
+    pub(crate) fn read_bytes<const N: usize>(&mut self) -> Result<&'a [u8; N], ErrorKind> {
         match self.bytes.split_first_chunk() {
             Some((chunk, rest)) => {
                 self.bytes = rest;
                 Ok(chunk)
             }
-            None => Err(Error::UnexpectedEof),
+            None => Err(ErrorKind::UnexpectedEof),
         }
     }
 
-    pub(crate) fn read_char(&mut self) -> Result<char, Error> {
+    pub(crate) fn read_char(&mut self) -> Result<char, ErrorKind> {
         let remaining = self.bytes.len();
         if remaining == 0 {
-            return Err(Error::UnexpectedEof);
+            return Err(ErrorKind::UnexpectedEof);
         }
         for n in 1..=remaining.min(4) {
             if let Ok(str) = std::str::from_utf8(&self.bytes[..n]) {
@@ -63,23 +138,68 @@ impl<'a> Reader<'a> {
                 return Ok(char);
             }
         }
-        Err(Error::InvalidUtf8Char)
+        Err(ErrorKind::InvalidUtf8Char)
+    }
+
+    pub(crate) fn dialect(&self) -> &'a dyn Dialect {
+        self.options.dialect.unwrap_or(&Strict)
     }
 
+    pub(crate) fn is_whitespace(&self, b: u8) -> bool {
+        let whitespace = match self.options.dialect {
+            None => matches!(b, b'\t' | b'\n' | b'\r' | b' '),
+            Some(dialect) => dialect.is_whitespace(b),
+        };
+        whitespace || self.options.extra_whitespace.contains(&b)
+    }
+
+    /// Skips whitespace, and comments if the dialect allows them.
+    ///
+    /// An unterminated block comment is left in place, to be reported by
+    /// the caller.
     pub(crate) fn skip_whitespace(&mut self) {
-        while let Some(b) = self.peek_byte() {
-            match b {
-                b'\t' | b'\n' | b'\r' | b' ' => {}
+        let comments = self.options.dialect.is_some_and(|d| d.comments());
+        loop {
+            match self.bytes {
+                [b, rest @ ..] if self.is_whitespace(*b) => self.bytes = rest,
+                [b'/', b'/', rest @ ..] if comments => {
+                    let end = rest
+                        .iter()
+                        .position(|&b| b == b'\n')
+                        .map_or(rest.len(), |i| i + 1);
+                    self.bytes = &rest[end..];
+                }
+                [b'/', b'*', rest @ ..] if comments => {
+                    let Some(end) = rest.windows(2).position(|w| w == b"*/") else {
+                        break;
+                    };
+                    self.bytes = &rest[end + 2..];
+                }
                 _ => break,
             }
-            self.read_byte().unwrap();
         }
     }
 
+    /// After a comma, consumes `close` and whitespace before it if the
+    /// dialect allows trailing commas, returning whether it did.
+    pub(crate) fn skip_trailing_comma(&mut self, close: u8) -> bool {
+        if !self.dialect().trailing_commas() {
+            return false;
+        }
+        let bytes = self.bytes;
+        self.skip_whitespace();
+        if self.peek_byte() == Some(close) {
+            self.bytes = &self.bytes[1..];
+            return true;
+        }
+        self.bytes = bytes;
+        false
+    }
+
     pub(crate) fn parse_slice<T>(
         &mut self,
-        f: impl FnOnce(&mut Reader) -> Result<T, Error>,
-    ) -> Result<(&'a [u8], T), Error> {
+        f: impl FnOnce(&mut Reader) -> Result<T, ErrorKind>,
+    ) -> Result<(&'a [u8], T), ErrorKind> {
         let bytes_start = self.bytes;
         let v = f(self)?;
 
@@ -92,3 +212,81 @@ impl<'a> Reader<'a> {
         Ok((slice, v))
     }
 }
+
+static DEFAULT_OPTIONS: Options = Options::DEFAULT;
+
+/// Parses a sequence of concatenated JSON values one at a time.
+pub struct Parser<'a> {
+    reader: Reader<'a>,
+}
+
+impl<'a> Parser<'a> {
+    pub fn new(bytes: &'a [u8]) -> Self {
+        Self::with_options(bytes, &DEFAULT_OPTIONS)
+    }
+
+    /// Like [`Parser::new`], but parses according to `options`.
+    ///
+    /// [`Options::allow_bom`] and [`Options::strip_xssi_prefix`] apply to the
+    /// start of the input, before the first value.
+    pub fn with_options(bytes: &'a [u8], options: &'a Options<'a>) -> Self {
+        let mut reader = Reader::new(bytes, options);
+        reader.skip_prefixes();
+        Self { reader }
+    }
+
+    /// Parses the next value, or returns `None` if only whitespace is left.
+    pub fn next_value(&mut self) -> Result<Option<Value>, Error> {
+        self.reader.skip_whitespace();
+        if self.reader.at_end() {
+            return Ok(None);
+        }
+        read_value(&mut self.reader)
+            .map(Some)
+            .map_err(|kind| Error::at(kind, self.reader.offset()))
+    }
+
+    /// The bytes that haven't been parsed yet.
+    pub fn remaining(&self) -> &'a [u8] {
+        self.reader.remaining()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parser() {
+        let mut parser = Parser::new(b" 1 [2]{\"a\": 3}\n");
+        assert_eq!(parser.next_value(), Ok(Some(1.into())));
+        assert_eq!(parser.remaining(), b"[2]{\"a\": 3}\n");
+        assert_eq!(parser.next_value(), Ok(Some(vec![2.into()].into())));
+        assert_eq!(
+            parser.next_value(),
+            Ok(Some(Value::from_json(br#"{"a": 3}"#).unwrap()))
+        );
+        assert_eq!(parser.remaining(), b"");
+        assert_eq!(parser.next_value(), Ok(None));
+
+        let mut parser = Parser::new(b"1 ]");
+        assert_eq!(parser.next_value(), Ok(Some(1.into())));
+        assert_eq!(
+            parser.next_value(),
+            Err(Error::at(ErrorKind::UnexpectedStartOfValue(b']'), 2))
+        );
+    }
+
+    #[test]
+    fn test_parser_prefixes() {
+        let options = Options {
+            allow_bom: true,
+            strip_xssi_prefix: true,
+            ..Options::default()
+        };
+        let mut parser = Parser::with_options(b"\xEF\xBB\xBF)]}'\n1 2", &options);
+        assert_eq!(parser.next_value(), Ok(Some(1.into())));
+        assert_eq!(parser.next_value(), Ok(Some(2.into())));
+        assert_eq!(parser.next_value(), Ok(None));
+    }
+}
@@ -0,0 +1,533 @@
+use std::collections::BTreeMap;
+
+use crate::{
+    array::{read_elements, Array},
+    error::{Error, ErrorKind},
+    object::{read_checked_key, read_members, Object},
+    options::Options,
+    parser::Reader,
+    read_value, skip_value,
+    string::JsonString,
+    Value,
+};
+
+/// Splits a JSON Pointer into its unescaped reference tokens.
+///
+/// Returns `None` if `ptr` is not a valid pointer.
+pub(crate) fn parse_pointer(ptr: &str) -> Option<Vec<String>> {
+    if ptr.is_empty() {
+        return Some(Vec::new());
+    }
+    let rest = ptr.strip_prefix('/')?;
+    rest.split('/').map(unescape_token).collect()
+}
+
+fn unescape_token(token: &str) -> Option<String> {
+    let mut res = String::with_capacity(token.len());
+    let mut chars = token.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '~' => match chars.next()? {
+                '0' => res.push('~'),
+                '1' => res.push('/'),
+                _ => return None,
+            },
+            c => res.push(c),
+        }
+    }
+    Some(res)
+}
+
+/// Parses an array index token, which must not have leading zeros.
+pub(crate) fn parse_index(token: &str) -> Option<usize> {
+    if token.is_empty()
+        || !token.bytes().all(|b| b.is_ascii_digit())
+        || (token.len() > 1 && token.starts_with('0'))
+    {
+        return None;
+    }
+    token.parse().ok()
+}
+
+/// Appends `key` as a reference token to the pointer `path`.
+pub(crate) fn push_token(path: &mut String, key: &str) {
+    path.push('/');
+    for c in key.chars() {
+        match c {
+            '~' => path.push_str("~0"),
+            '/' => path.push_str("~1"),
+            c => path.push(c),
+        }
+    }
+}
+
+/// Like [`push_token`], replacing lone surrogates in `key` with U+FFFD.
+pub(crate) fn push_key(path: &mut String, key: &JsonString) {
+    match key.as_str() {
+        Some(key) => push_token(path, key),
+        None => push_token(path, &key.clone().into_string_lossy()),
+    }
+}
+
+pub(crate) fn push_index(path: &mut String, index: usize) {
+    path.push('/');
+    path.push_str(&index.to_string());
+}
+
+/// Reads the value at `tokens` within the next value, skipping everything
+/// else.
+fn extract(reader: &mut Reader, tokens: &[String]) -> Result<Option<Value>, ErrorKind> {
+    let Some((token, rest)) = tokens.split_first() else {
+        return read_value(reader).map(Some);
+    };
+
+    reader.skip_whitespace();
+    let mut found = None;
+    match reader.peek_byte() {
+        Some(b'[') => {
+            let index = parse_index(token);
+            let mut i = 0;
+            read_elements(reader, |reader| {
+                if Some(i) == index {
+                    found = extract(reader, rest)?;
+                } else {
+                    skip_value(reader)?;
+                }
+                i += 1;
+                Ok(())
+            })?;
+        }
+        Some(b'{') => {
+            read_members(reader, read_checked_key, |reader, key| {
+                // Later duplicates win, like when parsing the whole value.
+                if key.as_str() == Some(token) {
+                    found = extract(reader, rest)?;
+                } else {
+                    skip_value(reader)?;
+                }
+                Ok(())
+            })?;
+        }
+        _ => {
+            skip_value(reader)?;
+            return Ok(None);
+        }
+    }
+
+    reader.skip_whitespace();
+    Ok(found)
+}
+
+#[derive(Default)]
+struct PathTree {
+    keep: bool,
+    children: BTreeMap<String, PathTree>,
+}
+
+impl PathTree {
+    fn project(&self, value: &Value) -> Option<Value> {
+        if self.keep {
+            return Some(value.clone());
+        }
+        match value {
+            Value::Object(obj) => {
+                let mut res = Object::new();
+                for (key, tree) in &self.children {
+                    if let Some(v) = obj.get(key).and_then(|v| tree.project(v)) {
+                        res.insert(key.as_str().into(), v);
+                    }
+                }
+                (!res.is_empty()).then_some(Value::Object(res))
+            }
+            Value::Array(arr) => {
+                let mut selected: Vec<_> = self
+                    .children
+                    .iter()
+                    .filter_map(|(token, tree)| Some((parse_index(token)?, tree)))
+                    .collect();
+                selected.sort_by_key(|(i, _)| *i);
+                let res: Vec<_> = selected
+                    .into_iter()
+                    .filter_map(|(i, tree)| tree.project(arr.get(i)?))
+                    .collect();
+                (!res.is_empty()).then_some(Value::from(res))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl Value {
+    /// Looks up a value by [JSON Pointer](https://www.rfc-editor.org/rfc/rfc6901).
+    pub fn pointer(&self, ptr: &str) -> Option<&Value> {
+        let mut value = self;
+        for token in parse_pointer(ptr)? {
+            value = match value {
+                Value::Object(obj) => obj.get(&token)?,
+                Value::Array(arr) => arr.get(parse_index(&token)?)?,
+                _ => return None,
+            };
+        }
+        Some(value)
+    }
+
+    /// Serializes the value at the JSON Pointer `ptr`, without cloning it.
+    /// Returns `None` if there is no such value.
+    pub fn subtree_to_string(&self, ptr: &str) -> Option<String> {
+        self.pointer(ptr).map(Value::to_string)
+    }
+
+    /// Like [`Value::pointer`], but returns a mutable reference.
+    pub fn pointer_mut(&mut self, ptr: &str) -> Option<&mut Value> {
+        let mut value = self;
+        for token in parse_pointer(ptr)? {
+            value = match value {
+                Value::Object(obj) => obj.get_mut(&token)?,
+                Value::Array(arr) => arr.get_mut(parse_index(&token)?)?,
+                _ => return None,
+            };
+        }
+        Some(value)
+    }
+
+    /// Returns the value at the JSON Pointer `ptr`, creating it and any
+    /// missing containers on the way as needed.
+    ///
+    /// A `null` along the path is replaced with an array if the next token
+    /// is an array index or `-`, and otherwise with an object. The index one
+    /// past the end of an array and `-` both append a `null`. The returned
+    /// slot is `null` if it was just created.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `ptr` is not a valid pointer, if it passes through a scalar
+    /// or uses a non-index token on an array, or if an index is more than one
+    /// past the end of an array.
+    pub fn entry_path(&mut self, ptr: &str) -> &mut Value {
+        let tokens = parse_pointer(ptr).expect("invalid JSON Pointer");
+        let mut value = self;
+        for token in tokens {
+            let index = match token.as_str() {
+                "-" => None,
+                token => parse_index(token),
+            };
+            if matches!(value, Value::Null) && (token == "-" || index.is_some()) {
+                *value = Value::Array(Array::new());
+            }
+            value = match value {
+                Value::Array(arr) => {
+                    let index = match index {
+                        Some(index) => index,
+                        None if token == "-" => arr.len(),
+                        None => panic!("expected an array index, got {token:?}"),
+                    };
+                    assert!(
+                        index <= arr.len(),
+                        "array index {index} is past the end of an array of length {}",
+                        arr.len()
+                    );
+                    if index == arr.len() {
+                        arr.push(Value::Null);
+                    }
+                    &mut arr[index]
+                }
+                value => value
+                    .as_object_mut_or_insert()
+                    .entry(token.into())
+                    .or_insert(Value::Null),
+            };
+        }
+        value
+    }
+
+    /// Parses only the value at the JSON Pointer `ptr` out of `bytes`.
+    ///
+    /// Everything else is validated but skipped without being built, which
+    /// makes this much cheaper than [`Value::from_json`] followed by
+    /// [`Value::pointer`] for large documents. Returns `Ok(None)` if the path
+    /// is not present or `ptr` is not a valid pointer.
+    pub fn extract_pointer(bytes: &[u8], ptr: &str) -> Result<Option<Value>, Error> {
+        Self::extract_pointer_with_options(bytes, ptr, &Options::default())
+    }
+
+    /// Like [`Value::extract_pointer`], but parses according to `options`.
+    pub fn extract_pointer_with_options(
+        bytes: &[u8],
+        ptr: &str,
+        options: &Options,
+    ) -> Result<Option<Value>, Error> {
+        let Some(tokens) = parse_pointer(ptr) else {
+            return Reader::read_all_with_options(bytes, options, skip_value).map(|()| None);
+        };
+        Reader::read_all_with_options(bytes, options, |reader| extract(reader, &tokens))
+    }
+
+    /// Removes and returns the value at the JSON Pointer `ptr`.
+    ///
+    /// Removing an array element shifts the following elements down. The
+    /// root (`""`) can't be removed, so it's a no-op returning `None`.
+    pub fn pointer_remove(&mut self, ptr: &str) -> Option<Value> {
+        let mut tokens = parse_pointer(ptr)?;
+        let last = tokens.pop()?;
+
+        let mut parent = self;
+        for token in tokens {
+            parent = match parent {
+                Value::Object(obj) => obj.get_mut(&token)?,
+                Value::Array(arr) => arr.get_mut(parse_index(&token)?)?,
+                _ => return None,
+            };
+        }
+
+        match parent {
+            Value::Object(obj) => obj.remove(&last),
+            Value::Array(arr) => {
+                let index = parse_index(&last).filter(|&i| i < arr.len())?;
+                Some(arr.remove(index))
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns a copy of the value containing only the given JSON Pointer
+    /// paths, along with the containers leading to them.
+    ///
+    /// Paths that are invalid or not present are skipped, and containers
+    /// where nothing was kept are left out. Selected array elements keep their
+    /// relative order, but are renumbered from zero, so projecting `/a/2` out
+    /// of `{"a": [0, 1, 2]}` gives `{"a": [2]}`.
+    ///
+    /// If nothing is kept, an empty object or array is returned for a
+    /// container, and `null` for a scalar.
+    pub fn project(&self, keep: &[&str]) -> Value {
+        let mut tree = PathTree::default();
+        for tokens in keep.iter().filter_map(|ptr| parse_pointer(ptr)) {
+            let mut node = &mut tree;
+            for token in tokens {
+                node = node.children.entry(token).or_default();
+            }
+            node.keep = true;
+        }
+
+        tree.project(self).unwrap_or_else(|| match self {
+            Value::Object(_) => Value::Object(Object::new()),
+            Value::Array(_) => Value::Array(Array::new()),
+            _ => Value::Null,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Json5;
+
+    #[test]
+    fn test_parse_pointer() {
+        assert_eq!(parse_pointer(""), Some(vec![]));
+        assert_eq!(parse_pointer("/"), Some(vec!["".into()]));
+        assert_eq!(
+            parse_pointer("/a~1b/c~0d/0"),
+            Some(vec!["a/b".into(), "c~d".into(), "0".into()])
+        );
+        assert_eq!(parse_pointer("a"), None);
+        assert_eq!(parse_pointer("/a~2"), None);
+    }
+
+    #[test]
+    fn test_pointer() {
+        let v = Value::from_json(br#"{"a": [1, {"b/c": true}], "": 2}"#).unwrap();
+        assert_eq!(v.pointer(""), Some(&v));
+        assert_eq!(v.pointer("/a/1/b~1c"), Some(&Value::Bool(true)));
+        assert_eq!(v.pointer("/"), Some(&Value::from(2)));
+        assert_eq!(v.pointer("/a/01"), None);
+        assert_eq!(v.pointer("/a/2"), None);
+        assert_eq!(v.pointer("/a/0/x"), None);
+    }
+
+    #[test]
+    fn test_subtree_to_string() {
+        let value =
+            Value::from_json(br#"{"a": [{"b": [1, 2.5], "c": "d"}, 3], "e": null}"#).unwrap();
+        let subtree = value.subtree_to_string("/a/0").unwrap();
+        assert_eq!(subtree, r#"{"b":[1,2.5],"c":"d"}"#);
+        assert_eq!(subtree, value["a"][0].to_string());
+        assert_eq!(value.subtree_to_string("").unwrap(), value.to_string());
+        assert_eq!(value.subtree_to_string("/a/2"), None);
+        assert_eq!(value.subtree_to_string("a"), None);
+    }
+
+    #[test]
+    fn test_pointer_remove() {
+        let mut v = Value::from_json(br#"{"a": [10, 11, 12], "b": {"c": null}}"#).unwrap();
+        assert_eq!(v.pointer_remove("/a/1"), Some(Value::from(11)));
+        assert_eq!(v.pointer("/a/1"), Some(&Value::from(12)));
+        assert_eq!(v.pointer("/a/2"), None);
+
+        assert_eq!(v.pointer_remove("/b/c"), Some(Value::Null));
+        assert_eq!(v.pointer_remove("/b/c"), None);
+        assert_eq!(v.pointer_remove("/a/5"), None);
+        assert_eq!(v.pointer_remove(""), None);
+        assert_eq!(v, Value::from_json(br#"{"a": [10, 12], "b": {}}"#).unwrap());
+    }
+
+    #[test]
+    fn test_extract_pointer() {
+        let big = format!("[{}]", vec![r#"{"x": "\u1234 skip me"}"#; 1000].join(","));
+        let json = format!(
+            r#"{{"z": {big}, "a": [{big}, 1, {{"b": {{"c": [true]}}, "d": {big}}}], "y": {big}}}"#
+        );
+        let json = json.as_bytes();
+
+        assert_eq!(
+            Value::extract_pointer(json, "/a/2/b"),
+            Ok(Some(Value::from_json(br#"{"c": [true]}"#).unwrap()))
+        );
+        assert_eq!(
+            Value::extract_pointer(json, "/a/2/b"),
+            Ok(Value::from_json(json).unwrap().pointer("/a/2/b").cloned())
+        );
+        assert_eq!(Value::extract_pointer(json, "/a/1"), Ok(Some(1.into())));
+        assert_eq!(Value::extract_pointer(json, "/a/3"), Ok(None));
+        assert_eq!(Value::extract_pointer(json, "/a/1/x"), Ok(None));
+        assert_eq!(Value::extract_pointer(json, "/missing"), Ok(None));
+        assert_eq!(Value::extract_pointer(json, "bad"), Ok(None));
+        assert_eq!(
+            Value::extract_pointer(br#"{"a": 1, "a": 2}"#, "/a"),
+            Ok(Some(2.into()))
+        );
+    }
+
+    #[test]
+    fn test_extract_pointer_validates_skipped_values() {
+        assert_eq!(
+            Value::extract_pointer(br#"{"a": 1, "b": [nul]}"#, "/a"),
+            Err(Error::at(ErrorKind::ExpectedNull, 19))
+        );
+        assert_eq!(
+            Value::extract_pointer(br#"{"a": 1, "b": "\x"}"#, "/a"),
+            Err(Error::at(ErrorKind::UnexpectedEscape(b'x'), 17))
+        );
+        assert_eq!(
+            Value::extract_pointer(br#"{"a": 1} 2"#, "/a"),
+            Err(Error::at(ErrorKind::TrailingData, 9))
+        );
+    }
+
+    #[test]
+    fn test_extract_pointer_skips_with_options() {
+        let forbidden = Options {
+            forbidden_keys: &["__proto__"],
+            ..Options::default()
+        };
+        let surrogates = Options {
+            reject_lone_surrogates: true,
+            ..Options::default()
+        };
+        let escapes = Options {
+            max_escapes_per_string: Some(2),
+            ..Options::default()
+        };
+        let cases: [(&Options, &[u8]); 6] = [
+            (&forbidden, br#"{"a": 1, "b": {"__proto__": 1}}"#),
+            (&surrogates, br#"{"a": 1, "b": "\ud800x"}"#),
+            (&surrogates, br#"{"a": 1, "b": ["\ud800\u0041"]}"#),
+            (&surrogates, br#"{"a": 1, "\udc00": 2}"#),
+            (&escapes, br#"{"a": 1, "b": "\n\n\n"}"#),
+            (&escapes, br#"{"a": 1, "\t\t\t": 2}"#),
+        ];
+        for (options, json) in cases {
+            assert_eq!(
+                Value::extract_pointer_with_options(json, "/a", options),
+                Err(Value::from_json_with_options(json, options).unwrap_err())
+            );
+        }
+
+        assert_eq!(
+            Value::extract_pointer_with_options(
+                br#"{"a": 1, "b": "\ud800\udc00\n"}"#,
+                "/a",
+                &Options {
+                    max_escapes_per_string: Some(4),
+                    ..surrogates
+                }
+            ),
+            Ok(Some(1.into()))
+        );
+    }
+
+    #[test]
+    fn test_extract_pointer_with_options() {
+        let json = b"{a: [1, {b: 'x',},], /* c */ d: 2,}";
+        assert!(Value::extract_pointer(json, "/a/1/b").is_err());
+
+        let options = Options {
+            dialect: Some(&Json5),
+            ..Options::default()
+        };
+        assert_eq!(
+            Value::extract_pointer_with_options(json, "/a/1/b", &options),
+            Ok(Some("x".into()))
+        );
+        assert_eq!(
+            Value::extract_pointer_with_options(json, "/d", &options),
+            Ok(Some(2.into()))
+        );
+    }
+
+    #[test]
+    fn test_project() {
+        let v = Value::from_json(
+            br#"{
+                "user": {"name": "a", "email": "b", "password": "c"},
+                "posts": [1, 2, 3],
+                "token": "d"
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            v.project(&["/user/name", "/user/email", "/user/missing", "/nope/x"]),
+            Value::from_json(br#"{"user": {"name": "a", "email": "b"}}"#).unwrap()
+        );
+        assert_eq!(
+            v.project(&["/posts/2", "/posts/0", "/posts/7"]),
+            Value::from_json(br#"{"posts": [1, 3]}"#).unwrap()
+        );
+        assert_eq!(v.project(&["/missing"]), Value::Object(Object::new()));
+        assert_eq!(v.project(&[""]), v);
+    }
+
+    #[test]
+    fn test_entry_path() {
+        let mut value = Value::Object(Object::new());
+        *value.entry_path("/a/b/c") = 1.into();
+        *value.entry_path("/a/b/d") = 2.into();
+        *value.entry_path("/a/list/0/x") = true.into();
+        value.entry_path("/a/list/1");
+        value.entry_path("/a/list/-");
+        assert_eq!(
+            value,
+            Value::from_json(
+                br#"{"a": {"b": {"c": 1, "d": 2}, "list": [{"x": true}, null, null]}}"#
+            )
+            .unwrap()
+        );
+        assert_eq!(value.entry_path("/a/b/c"), &Value::from(1));
+        let copy = value.clone();
+        assert_eq!(value.entry_path(""), &copy);
+    }
+
+    #[test]
+    #[should_panic = "expected an object or null"]
+    fn test_entry_path_through_scalar() {
+        let mut value = Value::from_json(br#"{"a": 1}"#).unwrap();
+        value.entry_path("/a/b");
+    }
+
+    #[test]
+    #[should_panic = "past the end"]
+    fn test_entry_path_index_past_end() {
+        let mut value = Value::from_json(b"[1]").unwrap();
+        value.entry_path("/18446744073709551615");
+    }
+}
@@ -0,0 +1,356 @@
+use std::{
+    borrow::Borrow,
+    collections::{BTreeMap, HashSet},
+    fmt::Display,
+    ops::Deref,
+    sync::{Arc, Mutex},
+};
+
+use crate::{
+    array::read_elements,
+    error::{Error, ErrorKind},
+    number::Number,
+    object::{read_checked_key, read_members},
+    options::Options,
+    parser::Reader,
+    read_leaf,
+    string::{JsonStr, JsonString},
+    Value,
+};
+
+/// A reference counted [`JsonString`], cheap to clone.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SharedJsonString(Arc<JsonString>);
+
+impl Deref for SharedJsonString {
+    type Target = JsonString;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl Borrow<JsonStr> for SharedJsonString {
+    fn borrow(&self) -> &JsonStr {
+        (*self.0).borrow()
+    }
+}
+
+impl Display for SharedJsonString {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl From<SharedJsonString> for JsonString {
+    fn from(value: SharedJsonString) -> Self {
+        Arc::unwrap_or_clone(value.0)
+    }
+}
+
+/// A set of interned strings, for sharing repeated strings between and
+/// within documents.
+#[derive(Debug, Default)]
+pub struct StringPool {
+    strings: HashSet<SharedJsonString>,
+}
+
+impl StringPool {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.strings.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.strings.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.strings.clear();
+    }
+
+    /// Returns the pooled copy of `s`, adding it to the pool if needed.
+    pub fn intern(&mut self, s: &JsonStr) -> SharedJsonString {
+        if let Some(shared) = self.strings.get(s) {
+            return shared.clone();
+        }
+        let shared = SharedJsonString(Arc::new(s.into()));
+        self.strings.insert(shared.clone());
+        shared
+    }
+
+    /// Like [`StringPool::intern`], but reuses the allocation of `s` if it's
+    /// not already pooled.
+    pub fn intern_owned(&mut self, s: JsonString) -> SharedJsonString {
+        if let Some(shared) = self.strings.get::<JsonStr>(s.borrow()) {
+            return shared.clone();
+        }
+        let shared = SharedJsonString(Arc::new(s));
+        self.strings.insert(shared.clone());
+        shared
+    }
+
+    /// Converts `value`, interning all object keys and string values.
+    pub fn share(&mut self, value: Value) -> SharedValue {
+        match value {
+            Value::Null => SharedValue::Null,
+            Value::Bool(v) => SharedValue::Bool(v),
+            Value::Number(v) => SharedValue::Number(v),
+            Value::String(v) => SharedValue::String(self.intern_owned(v)),
+            Value::Array(arr) => {
+                SharedValue::Array(arr.into_iter().map(|v| self.share(v)).collect())
+            }
+            Value::Object(obj) => SharedValue::Object(
+                obj.into_iter()
+                    .map(|(k, v)| (self.intern_owned(k), self.share(v)))
+                    .collect(),
+            ),
+        }
+    }
+}
+
+/// Mirror of [`Value`] where strings are shared through a [`StringPool`].
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum SharedValue {
+    Null,
+    Bool(bool),
+    Number(Number),
+    String(SharedJsonString),
+    Array(Vec<SharedValue>),
+    Object(BTreeMap<SharedJsonString, SharedValue>),
+}
+
+impl SharedValue {
+    /// Parses `bytes`, interning strings in [`Options::string_pool`], or in a
+    /// pool private to this document if it's not set.
+    ///
+    /// With [`Options::dedup_scalars`], strings are interned as soon as they
+    /// are read.
+    pub fn from_json_with_options(bytes: &[u8], options: &Options) -> Result<Self, Error> {
+        let mut private_pool;
+        let mut shared_pool;
+        let pool = match &options.string_pool {
+            Some(pool) => {
+                shared_pool = pool.lock().unwrap();
+                &mut *shared_pool
+            }
+            None => {
+                private_pool = StringPool::new();
+                &mut private_pool
+            }
+        };
+        if !options.dedup_scalars {
+            let value = Value::from_json_with_options(bytes, options)?;
+            return Ok(pool.share(value));
+        }
+        Reader::read_all_with_options(bytes, options, |reader| {
+            reader.skip_whitespace();
+            if reader.at_end() {
+                return Err(ErrorKind::EmptyInput);
+            }
+            read_shared(reader, pool)
+        })
+    }
+
+    pub fn to_value(&self) -> Value {
+        match self {
+            SharedValue::Null => Value::Null,
+            SharedValue::Bool(v) => Value::Bool(*v),
+            SharedValue::Number(v) => Value::Number(v.clone()),
+            SharedValue::String(v) => Value::String((**v).clone()),
+            SharedValue::Array(arr) => arr
+                .iter()
+                .map(SharedValue::to_value)
+                .collect::<Vec<_>>()
+                .into(),
+            SharedValue::Object(obj) => obj
+                .iter()
+                .map(|(k, v)| ((**k).clone(), v.to_value()))
+                .collect::<BTreeMap<_, _>>()
+                .into(),
+        }
+    }
+}
+
+/// Reads a value like [`crate::read_value`], interning strings in `pool`.
+fn read_shared(reader: &mut Reader, pool: &mut StringPool) -> Result<SharedValue, ErrorKind> {
+    reader.skip_whitespace();
+
+    let Some(b) = reader.peek_byte() else {
+        return Err(ErrorKind::UnexpectedEof);
+    };
+
+    let value = match b {
+        b'[' => {
+            let mut elements = Vec::new();
+            read_elements(reader, |reader| {
+                let value = read_shared(reader, pool)?;
+                reader.allocate(size_of::<SharedValue>())?;
+                elements.push(value);
+                Ok(())
+            })?;
+            if reader.options().sort_arrays {
+                elements.sort();
+            }
+            SharedValue::Array(elements)
+        }
+        b'{' => {
+            let mut members = BTreeMap::new();
+            read_members(reader, read_checked_key, |reader, key| {
+                let value = read_shared(reader, pool)?;
+                reader.allocate(size_of::<(SharedJsonString, SharedValue)>())?;
+                members.insert(pool.intern_owned(key), value);
+                Ok(())
+            })?;
+            SharedValue::Object(members)
+        }
+        _ => pool.share(read_leaf(reader, b)?),
+    };
+
+    reader.skip_whitespace();
+    Ok(value)
+}
+
+/// Handle to a [`StringPool`] shared between parses.
+pub type SharedStringPool = Arc<Mutex<StringPool>>;
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{Dialect, Json5, Jsonc};
+
+    #[test]
+    fn test_intern() {
+        let mut pool = StringPool::new();
+        let a = pool.intern("abc".into());
+        let b = pool.intern_owned("abc".into());
+        assert!(Arc::ptr_eq(&a.0, &b.0));
+        assert_eq!(*a, JsonString::from("abc"));
+        assert_eq!(pool.len(), 1);
+
+        pool.intern("def".into());
+        assert_eq!(pool.len(), 2);
+    }
+
+    #[test]
+    fn test_shared_parse() {
+        let pool = SharedStringPool::default();
+        let options = Options {
+            string_pool: Some(pool.clone()),
+            ..Options::default()
+        };
+        let json = br#"[{"kind": "a"}, {"kind": "a"}, {"kind": "b"}]"#;
+        let first = SharedValue::from_json_with_options(json, &options).unwrap();
+        let second = SharedValue::from_json_with_options(json, &options).unwrap();
+        assert_eq!(pool.lock().unwrap().len(), 3);
+
+        let SharedValue::Array(first) = first else {
+            panic!("expected array");
+        };
+        let SharedValue::Array(second) = second else {
+            panic!("expected array");
+        };
+        let kind = |v: &SharedValue| match v {
+            SharedValue::Object(obj) => obj.get(JsonStr::from_str("kind")).unwrap().clone(),
+            _ => panic!("expected object"),
+        };
+        let (SharedValue::String(a), SharedValue::String(b)) = (kind(&first[0]), kind(&second[1]))
+        else {
+            panic!("expected strings");
+        };
+        assert!(Arc::ptr_eq(&a.0, &b.0));
+
+        assert_eq!(
+            SharedValue::Array(first).to_value(),
+            Value::from_json(json).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_dedup_scalars() {
+        let json = br#" [{"kind": "a", "n": [2, 1]}, {"kind": "a"}, "a", {}, []] "#;
+        let options = Options {
+            dedup_scalars: true,
+            ..Options::default()
+        };
+        let value = SharedValue::from_json_with_options(json, &options).unwrap();
+        assert_eq!(
+            value,
+            SharedValue::from_json_with_options(json, &Options::default()).unwrap()
+        );
+
+        let SharedValue::Array(arr) = value else {
+            panic!("expected array");
+        };
+        let (SharedValue::Object(obj), SharedValue::String(s)) = (&arr[0], &arr[2]) else {
+            panic!("expected object and string");
+        };
+        let SharedValue::String(kind) = &obj[JsonStr::from_str("kind")] else {
+            panic!("expected string");
+        };
+        assert!(Arc::ptr_eq(&kind.0, &s.0));
+
+        for (json, err, offset) in [
+            (&b" "[..], ErrorKind::EmptyInput, 1),
+            (b"[1 2]", ErrorKind::ExpectedCommaOrRightBracket(b'2'), 4),
+            (br#"{"a" 1}"#, ErrorKind::ExpectedColon(b'1'), 6),
+            (b"[1] 2", ErrorKind::TrailingData, 4),
+        ] {
+            assert_eq!(
+                SharedValue::from_json_with_options(json, &options),
+                Err(Error::at(err, offset))
+            );
+        }
+    }
+
+    #[test]
+    fn test_dedup_scalars_dialects() {
+        fn upper(v: Value) -> Value {
+            match v {
+                Value::String(s) => Value::String(s.as_str().unwrap().to_uppercase().into()),
+                v => v,
+            }
+        }
+
+        for (dialect, json) in [
+            (
+                &Jsonc as &'static dyn Dialect,
+                &br#"[1, 2, /* c */ {"a": "x", "b": ["y",],},]"#[..],
+            ),
+            (&Json5, br#"[1, 2, /* c */ {a: 'x', "b": ["y",],},]"#),
+        ] {
+            for value_transform in [None, Some(upper as fn(Value) -> Value)] {
+                let options = Options {
+                    dialect: Some(dialect),
+                    value_transform,
+                    ..Options::default()
+                };
+                let deduped = Options {
+                    dedup_scalars: true,
+                    ..options.clone()
+                };
+                let value = SharedValue::from_json_with_options(json, &options).unwrap();
+                assert_eq!(
+                    SharedValue::from_json_with_options(json, &deduped),
+                    Ok(value)
+                );
+            }
+        }
+
+        let options = Options {
+            dialect: Some(&Json5),
+            value_transform: Some(upper),
+            dedup_scalars: true,
+            ..Options::default()
+        };
+        assert_eq!(
+            SharedValue::from_json_with_options(b"{a: ['x',]}", &options)
+                .unwrap()
+                .to_value(),
+            Value::from_json(br#"{"a": ["X"]}"#).unwrap()
+        );
+    }
+}
@@ -0,0 +1,122 @@
+use crate::{
+    array::skip_array,
+    error::{Error, ErrorKind},
+    object::skip_object,
+    options::Options,
+    parser::Reader,
+    read_scalar, skip_value, Value,
+};
+
+/// The type of a value, and the number of members of a container.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Shape {
+    Null,
+    Bool,
+    Number,
+    String,
+    Array(usize),
+    Object(usize),
+}
+
+impl Value {
+    /// Determines the shape of the value in `bytes` without building it.
+    ///
+    /// The whole input is still validated.
+    pub fn probe(bytes: &[u8]) -> Result<Shape, Error> {
+        Self::probe_with_options(bytes, &Options::default())
+    }
+
+    /// Like [`Value::probe`], but parses according to `options`.
+    pub fn probe_with_options(bytes: &[u8], options: &Options) -> Result<Shape, Error> {
+        Reader::read_all_with_options(bytes, options, |reader| {
+            reader.skip_whitespace();
+            let shape = match reader.peek_byte() {
+                None => return Err(ErrorKind::EmptyInput),
+                Some(b'[') => Shape::Array(skip_array(reader)?),
+                Some(b'{') => Shape::Object(skip_object(reader)?),
+                Some(b'"') => {
+                    skip_value(reader)?;
+                    Shape::String
+                }
+                // The dialect and options decide what other scalars are, and
+                // they are cheap to read.
+                Some(b) => match read_scalar(reader, b)? {
+                    Value::Null => Shape::Null,
+                    Value::Bool(_) => Shape::Bool,
+                    Value::Number(_) => Shape::Number,
+                    Value::String(_) => Shape::String,
+                    Value::Array(_) | Value::Object(_) => unreachable!(),
+                },
+            };
+            reader.skip_whitespace();
+            Ok(shape)
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_probe() {
+        assert_eq!(Value::probe(b" null "), Ok(Shape::Null));
+        assert_eq!(Value::probe(b"false"), Ok(Shape::Bool));
+        assert_eq!(Value::probe(b"-1.5"), Ok(Shape::Number));
+        assert_eq!(Value::probe(br#""[""#), Ok(Shape::String));
+        assert_eq!(Value::probe(b"[]"), Ok(Shape::Array(0)));
+        assert_eq!(
+            Value::probe(br#"[1, [2, 3], {"a": 4}]"#),
+            Ok(Shape::Array(3))
+        );
+        assert_eq!(
+            Value::probe(br#"{"a": [1, 2], "b": {}}"#),
+            Ok(Shape::Object(2))
+        );
+    }
+
+    #[test]
+    fn test_probe_invalid() {
+        assert_eq!(Value::probe(b""), Err(Error::at(ErrorKind::EmptyInput, 0)));
+        assert_eq!(
+            Value::probe(b"[1, x]"),
+            Err(Error::at(ErrorKind::UnexpectedStartOfValue(b'x'), 4))
+        );
+        assert_eq!(
+            Value::probe(b"nul"),
+            Err(Error::at(ErrorKind::UnexpectedEof, 0))
+        );
+        assert_eq!(
+            Value::probe(b"-"),
+            Err(Error::at(ErrorKind::UnexpectedEof, 1))
+        );
+        assert_eq!(
+            Value::probe(b"1 2"),
+            Err(Error::at(ErrorKind::TrailingData, 2))
+        );
+    }
+
+    #[test]
+    fn test_probe_with_options() {
+        let options = Options {
+            forbidden_keys: &["__proto__"],
+            reject_lone_surrogates: true,
+            max_escapes_per_string: Some(2),
+            ..Options::default()
+        };
+        assert_eq!(
+            Value::probe_with_options(br#"{"a": 1, "b": {"c": "\n"}}"#, &options),
+            Ok(Shape::Object(2))
+        );
+        for json in [
+            &br#"{"a": 1, "b": {"__proto__": 1}}"#[..],
+            br#"{"a": 1, "b": ["\udc00"]}"#,
+            br#""\n\n\n""#,
+        ] {
+            assert_eq!(
+                Value::probe_with_options(json, &options),
+                Err(Value::from_json_with_options(json, &options).unwrap_err())
+            );
+        }
+    }
+}
@@ -0,0 +1,201 @@
+use crate::{
+    pointer::{push_index, push_key},
+    string::JsonString,
+    Value,
+};
+
+/// A minimal schema describing the expected shape of a [`Value`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Schema {
+    /// Matches any value.
+    Any,
+    Null,
+    Bool,
+    Number,
+    String,
+    /// An array whose elements all match the given schema.
+    Array(Box<Schema>),
+    /// An object with the given members. Members not listed are allowed and
+    /// not validated.
+    Object {
+        required: Vec<(JsonString, Schema)>,
+        optional: Vec<(JsonString, Schema)>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SchemaErrorKind {
+    MissingKey(JsonString),
+    WrongType {
+        expected: &'static str,
+        found: &'static str,
+    },
+}
+
+/// A schema violation at the value identified by the JSON Pointer `path`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaError {
+    pub path: String,
+    pub kind: SchemaErrorKind,
+}
+
+pub(crate) fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "bool",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+fn validate(value: &Value, schema: &Schema, path: &mut String, errors: &mut Vec<SchemaError>) {
+    let expected = match (schema, value) {
+        (Schema::Any, _)
+        | (Schema::Null, Value::Null)
+        | (Schema::Bool, Value::Bool(_))
+        | (Schema::Number, Value::Number(_))
+        | (Schema::String, Value::String(_)) => return,
+        (Schema::Array(schema), Value::Array(arr)) => {
+            for (i, v) in arr.iter().enumerate() {
+                let len = path.len();
+                push_index(path, i);
+                validate(v, schema, path, errors);
+                path.truncate(len);
+            }
+            return;
+        }
+        (Schema::Object { required, optional }, Value::Object(obj)) => {
+            for (key, schema) in required {
+                match obj.get(key) {
+                    Some(v) => validate_member(key, v, schema, path, errors),
+                    None => errors.push(SchemaError {
+                        path: path.clone(),
+                        kind: SchemaErrorKind::MissingKey(key.clone()),
+                    }),
+                }
+            }
+            for (key, schema) in optional {
+                if let Some(v) = obj.get(key) {
+                    validate_member(key, v, schema, path, errors);
+                }
+            }
+            return;
+        }
+        (Schema::Null, _) => "null",
+        (Schema::Bool, _) => "bool",
+        (Schema::Number, _) => "number",
+        (Schema::String, _) => "string",
+        (Schema::Array(_), _) => "array",
+        (Schema::Object { .. }, _) => "object",
+    };
+
+    errors.push(SchemaError {
+        path: path.clone(),
+        kind: SchemaErrorKind::WrongType {
+            expected,
+            found: type_name(value),
+        },
+    });
+}
+
+fn validate_member(
+    key: &JsonString,
+    value: &Value,
+    schema: &Schema,
+    path: &mut String,
+    errors: &mut Vec<SchemaError>,
+) {
+    let len = path.len();
+    push_key(path, key);
+    validate(value, schema, path, errors);
+    path.truncate(len);
+}
+
+impl Value {
+    /// Validates the value against `schema`, collecting all violations.
+    pub fn validate_schema(&self, schema: &Schema) -> Result<(), Vec<SchemaError>> {
+        let mut errors = Vec::new();
+        validate(self, schema, &mut String::new(), &mut errors);
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn user_schema() -> Schema {
+        Schema::Object {
+            required: vec![
+                ("name".into(), Schema::String),
+                ("tags".into(), Schema::Array(Box::new(Schema::String))),
+            ],
+            optional: vec![("age".into(), Schema::Number)],
+        }
+    }
+
+    #[test]
+    fn test_valid() {
+        let v = Value::from_json(br#"{"name": "a", "tags": ["x"], "extra": null}"#).unwrap();
+        assert_eq!(v.validate_schema(&user_schema()), Ok(()));
+    }
+
+    #[test]
+    fn test_missing_required_key() {
+        let v = Value::from_json(br#"{"tags": []}"#).unwrap();
+        assert_eq!(
+            v.validate_schema(&user_schema()),
+            Err(vec![SchemaError {
+                path: "".into(),
+                kind: SchemaErrorKind::MissingKey("name".into()),
+            }])
+        );
+    }
+
+    #[test]
+    fn test_wrong_type() {
+        let v = Value::from_json(br#"{"name": 1, "tags": ["x", null], "age": "old"}"#).unwrap();
+        assert_eq!(
+            v.validate_schema(&user_schema()),
+            Err(vec![
+                SchemaError {
+                    path: "/name".into(),
+                    kind: SchemaErrorKind::WrongType {
+                        expected: "string",
+                        found: "number",
+                    },
+                },
+                SchemaError {
+                    path: "/tags/1".into(),
+                    kind: SchemaErrorKind::WrongType {
+                        expected: "string",
+                        found: "null",
+                    },
+                },
+                SchemaError {
+                    path: "/age".into(),
+                    kind: SchemaErrorKind::WrongType {
+                        expected: "number",
+                        found: "string",
+                    },
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn test_path_escaping() {
+        let schema = Schema::Object {
+            required: vec![("a/b~c".into(), Schema::Null)],
+            optional: vec![],
+        };
+        let v = Value::from_json(br#"{"a/b~c": true}"#).unwrap();
+        assert_eq!(v.validate_schema(&schema).unwrap_err()[0].path, "/a~1b~0c");
+    }
+}
@@ -0,0 +1,902 @@
+use std::{
+    fmt::{self, Write as _},
+    io::{self, Write},
+};
+
+use crate::{
+    number::Number,
+    parser::BOM,
+    string::{JsonStr, JsonString},
+    Value,
+};
+
+/// How floats without a fractional part are written.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum IntegralStyle {
+    /// `2.0` is written as `2`.
+    #[default]
+    Bare,
+    /// `2.0` is written as `2.0`, so it can be told apart from an integer.
+    Dotted,
+}
+
+/// How numbers that are not finite are written.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum NonFinitePolicy {
+    /// Fail, as strict JSON can't represent them.
+    #[default]
+    Error,
+    /// Write `null`, like `serde_json` does.
+    Null,
+    /// Write `NaN`, `Infinity` or `-Infinity`, as in JSON5.
+    Literal,
+}
+
+/// Options controlling how JSON is serialized.
+#[derive(Debug, Clone, Default)]
+pub struct WriteOptions {
+    /// Prepend a UTF-8 byte order mark, which some Windows tools expect.
+    ///
+    /// This only applies to byte output, not to the `Display` impl.
+    pub emit_bom: bool,
+    /// Whether floats without a fractional part are written as `2` (the
+    /// default) or as `2.0`. Integers are always written as `2`.
+    pub integral_float_style: IntegralStyle,
+    /// Write floats with at least this magnitude with an exponent, like
+    /// `1e6`. Integers are not affected, so they still parse as integers.
+    pub exponent_threshold: Option<f64>,
+    /// Write non-zero floats with a magnitude below this with an exponent,
+    /// like `1.5e-7`.
+    pub tiny_exponent_threshold: Option<f64>,
+    /// Pretty print with this string, such as `"\t"` or `"  "`, per level of
+    /// nesting. It should only contain JSON whitespace to keep the output
+    /// valid.
+    pub indent: Option<String>,
+    /// When pretty printing, keep arrays and objects on a single line if the
+    /// whole line, including indentation and key, fits within this many
+    /// characters. A trailing comma is not counted, and a tab counts as one
+    /// character.
+    pub max_width: Option<usize>,
+    /// When pretty printing, write empty arrays and objects over two lines
+    /// instead of as `[]` and `{}`, unless kept on one line by `max_width`.
+    pub expand_empty: bool,
+    /// With [`NonFinitePolicy::Error`], the `*_with_options` methods fail
+    /// with [`io::ErrorKind::InvalidData`].
+    pub non_finite: NonFinitePolicy,
+    /// Write object keys that are identifiers, like `a_1` or `$b`, without
+    /// quotes as in JSON5. Other keys are still quoted.
+    pub unquoted_keys: bool,
+    /// When pretty printing, pad the keys of each object with spaces to the
+    /// width of its longest key, so that its values line up.
+    pub align_values: bool,
+}
+
+/// Whether `key` matches `[A-Za-z_$][A-Za-z0-9_$]*`.
+fn is_identifier(key: &str) -> bool {
+    let mut bytes = key.bytes();
+    bytes
+        .next()
+        .is_some_and(|b| b.is_ascii_alphabetic() || b == b'_' || b == b'$')
+        && bytes.all(|b| b.is_ascii_alphanumeric() || b == b'_' || b == b'$')
+}
+
+struct Serializer<'a, W> {
+    out: W,
+    options: &'a WriteOptions,
+    depth: usize,
+    /// Write containers on a single line, even when pretty printing.
+    inline: bool,
+    /// Object keys written before the remaining keys, in this order.
+    key_priority: &'a [&'a str],
+}
+
+impl<'a, W: fmt::Write> Serializer<'a, W> {
+    fn new(out: W, options: &'a WriteOptions) -> Self {
+        Self {
+            out,
+            options,
+            depth: 0,
+            inline: false,
+            key_priority: &[],
+        }
+    }
+
+    /// Writes `value`, which is preceded by `prefix_width` characters on the
+    /// current line after the indentation.
+    fn write_value(&mut self, value: &Value, prefix_width: usize) -> fmt::Result {
+        match value {
+            Value::Number(v) => self.write_number(v),
+            Value::Array(arr) if !arr.is_empty() || self.expand_empty() => {
+                if self.write_inline_if_fits(value, prefix_width)? {
+                    return Ok(());
+                }
+                self.out.write_char('[')?;
+                self.depth += 1;
+                for (i, v) in arr.iter().enumerate() {
+                    if i != 0 {
+                        self.write_comma()?;
+                    }
+                    self.write_newline()?;
+                    self.write_value(v, 0)?;
+                }
+                self.depth -= 1;
+                self.write_newline()?;
+                self.out.write_char(']')
+            }
+            Value::Object(obj) if !obj.is_empty() || self.expand_empty() => {
+                if self.write_inline_if_fits(value, prefix_width)? {
+                    return Ok(());
+                }
+                self.out.write_char('{')?;
+                self.depth += 1;
+                let priority = self.key_priority;
+                let first = priority
+                    .iter()
+                    .enumerate()
+                    .filter(|&(i, k)| !priority[..i].contains(k))
+                    .filter_map(|(_, k)| obj.as_inner().get_key_value(JsonStr::from_str(k)));
+                let rest = obj
+                    .as_inner()
+                    .iter()
+                    .filter(|(k, _)| !k.as_str().is_some_and(|k| priority.contains(&k)));
+                let key_width = if self.options.align_values && self.options.indent.is_some() {
+                    obj.as_inner().keys().map(|k| self.key_width(k)).max()
+                } else {
+                    None
+                };
+                for (i, (k, v)) in first.chain(rest).enumerate() {
+                    if i != 0 {
+                        self.write_comma()?;
+                    }
+                    self.write_newline()?;
+                    let mut prefix_width = match self.unquoted_key(k) {
+                        Some(k) => {
+                            self.out.write_str(k)?;
+                            k.len()
+                        }
+                        None => {
+                            write!(self.out, "{k}")?;
+                            k.serialized_len()
+                        }
+                    };
+                    if let Some(width) = key_width.filter(|&w| w > prefix_width) {
+                        write!(self.out, "{:1$}", "", width - prefix_width)?;
+                        prefix_width = width;
+                    }
+                    self.out.write_char(':')?;
+                    prefix_width += 1;
+                    if self.options.indent.is_some() {
+                        self.out.write_char(' ')?;
+                        prefix_width += 1;
+                    }
+                    self.write_value(v, prefix_width)?;
+                }
+                self.depth -= 1;
+                self.write_newline()?;
+                self.out.write_char('}')
+            }
+            _ => write!(self.out, "{value}"),
+        }
+    }
+
+    /// Returns `key` if it should be written without quotes.
+    fn unquoted_key<'k>(&self, key: &'k JsonString) -> Option<&'k str> {
+        key.as_str()
+            .filter(|k| self.options.unquoted_keys && is_identifier(k))
+    }
+
+    /// The number of characters `key` is written as.
+    fn key_width(&self, key: &JsonString) -> usize {
+        match self.unquoted_key(key) {
+            Some(k) => k.len(),
+            None => key.serialized_len(),
+        }
+    }
+
+    fn expand_empty(&self) -> bool {
+        self.options.expand_empty && self.options.indent.is_some() && !self.inline
+    }
+
+    /// Writes the optional BOM and the opening bracket of an [`ArrayWriter`].
+    fn write_stream_start(&mut self) -> fmt::Result {
+        if self.options.emit_bom {
+            self.out.write_char('\u{feff}')?;
+        }
+        self.out.write_char('[')
+    }
+
+    fn write_comma(&mut self) -> fmt::Result {
+        self.out.write_char(',')?;
+        if self.inline {
+            self.out.write_char(' ')?;
+        }
+        Ok(())
+    }
+
+    fn write_newline(&mut self) -> fmt::Result {
+        let Some(indent) = &self.options.indent else {
+            return Ok(());
+        };
+        if self.inline {
+            return Ok(());
+        }
+        self.out.write_char('\n')?;
+        for _ in 0..self.depth {
+            self.out.write_str(indent)?;
+        }
+        Ok(())
+    }
+
+    fn write_inline_if_fits(
+        &mut self,
+        value: &Value,
+        prefix_width: usize,
+    ) -> Result<bool, fmt::Error> {
+        let (Some(indent), Some(max_width)) = (&self.options.indent, self.options.max_width) else {
+            return Ok(false);
+        };
+        if self.inline {
+            return Ok(false);
+        }
+
+        let mut line = String::new();
+        let mut serializer = Serializer::new(&mut line, self.options);
+        serializer.inline = true;
+        serializer.key_priority = self.key_priority;
+        serializer.write_value(value, 0)?;
+
+        if self.depth * indent.chars().count() + prefix_width + line.chars().count() > max_width {
+            return Ok(false);
+        }
+        self.out.write_str(&line)?;
+        Ok(true)
+    }
+
+    fn write_number(&mut self, number: &Number) -> fmt::Result {
+        let v = number.as_f64();
+        if !v.is_finite() {
+            return match self.options.non_finite {
+                NonFinitePolicy::Error => Err(fmt::Error),
+                NonFinitePolicy::Null => self.out.write_str("null"),
+                NonFinitePolicy::Literal if v.is_nan() => self.out.write_str("NaN"),
+                NonFinitePolicy::Literal if v > 0.0 => self.out.write_str("Infinity"),
+                NonFinitePolicy::Literal => self.out.write_str("-Infinity"),
+            };
+        }
+
+        let exponent = number.is_f64()
+            && v != 0.0
+            && (self
+                .options
+                .exponent_threshold
+                .is_some_and(|t| v.abs() >= t)
+                || self
+                    .options
+                    .tiny_exponent_threshold
+                    .is_some_and(|t| v.abs() < t));
+        let s = if exponent {
+            format!("{v:e}")
+        } else {
+            number.to_string()
+        };
+        if self.options.integral_float_style == IntegralStyle::Dotted
+            && number.is_f64()
+            && !s.contains('.')
+        {
+            let (mantissa, exponent) = s.split_at(s.find('e').unwrap_or(s.len()));
+            write!(self.out, "{mantissa}.0{exponent}")
+        } else {
+            self.out.write_str(&s)
+        }
+    }
+}
+
+/// Adapts an `io::Write` to `fmt::Write`, keeping the underlying error.
+/// The error for a number that is not finite under [`NonFinitePolicy::Error`].
+fn non_finite_error() -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, "non-finite number")
+}
+
+struct IoWriter<W> {
+    inner: W,
+    error: Option<io::Error>,
+}
+
+impl<W> IoWriter<W> {
+    /// The error that made a write fail. If the writer itself didn't fail,
+    /// the value couldn't be serialized.
+    fn take_error(&mut self) -> io::Error {
+        self.error.take().unwrap_or_else(non_finite_error)
+    }
+}
+
+impl<W: Write> fmt::Write for IoWriter<W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.inner.write_all(s.as_bytes()).map_err(|e| {
+            self.error = Some(e);
+            fmt::Error
+        })
+    }
+}
+
+/// Collects up to `max_len` bytes, failing once more are written.
+struct CappedWriter {
+    out: String,
+    max_len: usize,
+    truncated: bool,
+}
+
+impl fmt::Write for CappedWriter {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let room = self.max_len - self.out.len();
+        if s.len() <= room {
+            self.out.push_str(s);
+            return Ok(());
+        }
+        let mut end = room;
+        while !s.is_char_boundary(end) {
+            end -= 1;
+        }
+        self.out.push_str(&s[..end]);
+        self.truncated = true;
+        Err(fmt::Error)
+    }
+}
+
+/// Writes a JSON array one element at a time, without holding the elements
+/// in memory.
+///
+/// The array is only complete once [`ArrayWriter::finish`] has been called.
+pub struct ArrayWriter<W> {
+    out: IoWriter<W>,
+    options: WriteOptions,
+    len: usize,
+}
+
+impl<W: Write> ArrayWriter<W> {
+    pub fn new(writer: W) -> Self {
+        Self::with_options(writer, WriteOptions::default())
+    }
+
+    pub fn with_options(writer: W, options: WriteOptions) -> Self {
+        Self {
+            out: IoWriter {
+                inner: writer,
+                error: None,
+            },
+            options,
+            len: 0,
+        }
+    }
+
+    /// Number of elements written so far.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn write_element(&mut self, value: &Value) -> io::Result<()> {
+        let first = self.len == 0;
+        let mut serializer = Serializer::new(&mut self.out, &self.options);
+        serializer.depth = 1;
+        let result = (|| {
+            if first {
+                serializer.write_stream_start()?;
+            } else {
+                serializer.write_comma()?;
+            }
+            serializer.write_newline()?;
+            serializer.write_value(value, 0)
+        })();
+        result.map_err(|fmt::Error| self.out.take_error())?;
+        self.len += 1;
+        Ok(())
+    }
+
+    /// Closes the array and returns the underlying writer.
+    pub fn finish(mut self) -> io::Result<W> {
+        let first = self.len == 0;
+        let mut serializer = Serializer::new(&mut self.out, &self.options);
+        let result = (|| {
+            if first {
+                serializer.write_stream_start()?;
+            } else {
+                serializer.write_newline()?;
+            }
+            serializer.out.write_char(']')
+        })();
+        result.map_err(|fmt::Error| self.out.take_error())?;
+        Ok(self.out.inner)
+    }
+}
+
+impl Value {
+    /// Like [`Value::to_writer`], but serializes according to `options`.
+    pub fn to_writer_with_options<W: Write>(
+        &self,
+        mut writer: W,
+        options: &WriteOptions,
+    ) -> io::Result<()> {
+        if options.emit_bom {
+            writer.write_all(BOM)?;
+        }
+        let mut serializer = Serializer::new(
+            IoWriter {
+                inner: writer,
+                error: None,
+            },
+            options,
+        );
+        serializer
+            .write_value(self, 0)
+            .map_err(|fmt::Error| serializer.out.take_error())
+    }
+
+    /// Like [`Value::to_bytes`], but serializes according to `options`.
+    ///
+    /// This only fails on numbers that are not finite, as described for
+    /// [`WriteOptions::non_finite`].
+    pub fn to_bytes_with_options(&self, options: &WriteOptions) -> io::Result<Vec<u8>> {
+        let mut bytes = Vec::with_capacity(BOM.len() + self.serialized_len_estimate());
+        self.to_writer_with_options(&mut bytes, options)?;
+        Ok(bytes)
+    }
+
+    /// Serializes the value to a string according to `options`.
+    ///
+    /// [`WriteOptions::emit_bom`] is ignored, as it only applies to byte
+    /// output. This only fails on numbers that are not finite, as described
+    /// for [`WriteOptions::non_finite`].
+    pub fn to_string_with_options(&self, options: &WriteOptions) -> io::Result<String> {
+        let mut out = String::with_capacity(self.serialized_len_estimate());
+        Serializer::new(&mut out, options)
+            .write_value(self, 0)
+            .map_err(|fmt::Error| non_finite_error())?;
+        Ok(out)
+    }
+
+    /// Serializes the value compactly for logging, cutting it off after at
+    /// most `max_len` bytes and appending `…` if it doesn't fit.
+    ///
+    /// Serialization stops at the cut, so this is cheap for large values,
+    /// but a truncated result is not valid JSON. Numbers that are not finite
+    /// are written like the `Display` impl does.
+    pub fn to_log_string(&self, max_len: usize) -> String {
+        let options = WriteOptions {
+            non_finite: NonFinitePolicy::Literal,
+            ..WriteOptions::default()
+        };
+        let mut out = CappedWriter {
+            out: String::with_capacity(max_len.min(self.serialized_len_estimate())),
+            max_len,
+            truncated: false,
+        };
+        let result = Serializer::new(&mut out, &options).write_value(self, 0);
+        if out.truncated {
+            out.out.push('…');
+        } else {
+            result.unwrap();
+        }
+        out.out
+    }
+
+    /// Serializes the value to a string, writing the object keys in
+    /// `priority` first, in that order, followed by the remaining keys in
+    /// sorted order.
+    ///
+    /// Fails on numbers that are not finite, as described for
+    /// [`WriteOptions::non_finite`].
+    pub fn to_string_with_key_priority(&self, priority: &[&str]) -> io::Result<String> {
+        let mut out = String::with_capacity(self.serialized_len_estimate());
+        let options = WriteOptions::default();
+        let mut serializer = Serializer::new(&mut out, &options);
+        serializer.key_priority = priority;
+        serializer
+            .write_value(self, 0)
+            .map_err(|fmt::Error| non_finite_error())?;
+        Ok(out)
+    }
+
+    /// Serializes the value to a string, pretty printed with an indentation
+    /// of two spaces.
+    ///
+    /// Fails on numbers that are not finite, as described for
+    /// [`WriteOptions::non_finite`].
+    pub fn to_string_pretty(&self) -> io::Result<String> {
+        self.to_string_pretty_with("  ")
+    }
+
+    /// Serializes the value to a string, pretty printed with `indent` per
+    /// level of nesting.
+    ///
+    /// Fails on numbers that are not finite, as described for
+    /// [`WriteOptions::non_finite`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `indent` contains anything but JSON whitespace.
+    pub fn to_string_pretty_with(&self, indent: &str) -> io::Result<String> {
+        assert!(
+            indent
+                .bytes()
+                .all(|b| matches!(b, b' ' | b'\t' | b'\n' | b'\r')),
+            "indent must only contain whitespace"
+        );
+        self.to_string_with_options(&WriteOptions {
+            indent: Some(indent.to_owned()),
+            ..WriteOptions::default()
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        error::{Error, ErrorKind},
+        options::Options,
+    };
+
+    #[test]
+    fn test_emit_bom() {
+        let value = Value::from_json(br#"{"a": [1, "b"]}"#).unwrap();
+        let options = WriteOptions {
+            emit_bom: true,
+            ..WriteOptions::default()
+        };
+        let bytes = value.to_bytes_with_options(&options).unwrap();
+        assert!(bytes.starts_with(b"\xEF\xBB\xBF"));
+        assert_eq!(&bytes[3..], value.to_bytes().unwrap());
+
+        assert_eq!(
+            Value::from_json(&bytes),
+            Err(Error::at(ErrorKind::UnexpectedStartOfValue(0xEF), 0))
+        );
+        let options = Options {
+            allow_bom: true,
+            ..Options::default()
+        };
+        assert_eq!(Value::from_json_with_options(&bytes, &options), Ok(value));
+    }
+
+    #[test]
+    fn test_default_options_match_display() {
+        let value =
+            Value::from_json(br#"{"a": [1, 2.0, -0.5, 1e300, "x\u0000"], "b": {"c": null}}"#)
+                .unwrap();
+        assert_eq!(
+            value
+                .to_string_with_options(&WriteOptions::default())
+                .unwrap(),
+            value.to_string()
+        );
+    }
+
+    #[test]
+    fn test_pretty() {
+        let value = Value::from_json(br#"{"a": [1, {"b": null}], "c": {}, "d": []}"#).unwrap();
+        assert_eq!(
+            value.to_string_pretty().unwrap(),
+            r#"{
+  "a": [
+    1,
+    {
+      "b": null
+    }
+  ],
+  "c": {},
+  "d": []
+}"#
+        );
+        assert_eq!(
+            Value::from_json(value.to_string_pretty().unwrap().as_bytes()),
+            Ok(value)
+        );
+    }
+
+    #[test]
+    fn test_pretty_with() {
+        let value = Value::from_json(br#"{"a": [1, {"b": null}], "c": {}}"#).unwrap();
+        assert_eq!(
+            value.to_string_pretty_with("\t").unwrap(),
+            "{\n\t\"a\": [\n\t\t1,\n\t\t{\n\t\t\t\"b\": null\n\t\t}\n\t],\n\t\"c\": {}\n}"
+        );
+        assert_eq!(
+            value.to_string_pretty_with("  ").unwrap(),
+            value.to_string_pretty().unwrap()
+        );
+        assert_eq!(
+            Value::from_json(value.to_string_pretty_with("\t").unwrap().as_bytes()),
+            Ok(value)
+        );
+    }
+
+    #[test]
+    #[should_panic = "indent must only contain whitespace"]
+    fn test_pretty_with_invalid_indent() {
+        Value::Array(vec![1.into()].into())
+            .to_string_pretty_with("-")
+            .unwrap();
+    }
+
+    #[test]
+    fn test_expand_empty() {
+        let value = Value::from_json(br#"{"a":{},"b":[]}"#).unwrap();
+        assert_eq!(
+            value.to_string_pretty().unwrap(),
+            r#"{
+  "a": {},
+  "b": []
+}"#
+        );
+
+        let options = WriteOptions {
+            indent: Some("  ".into()),
+            expand_empty: true,
+            ..WriteOptions::default()
+        };
+        assert_eq!(
+            value.to_string_with_options(&options).unwrap(),
+            r#"{
+  "a": {
+  },
+  "b": [
+  ]
+}"#
+        );
+    }
+
+    #[test]
+    fn test_max_width() {
+        let value = Value::from_json(br#"{"a": [1, 2, 3], "b": 1}"#).unwrap();
+        let with_width = |max_width| {
+            value
+                .to_string_with_options(&WriteOptions {
+                    indent: Some("  ".into()),
+                    max_width: Some(max_width),
+                    ..WriteOptions::default()
+                })
+                .unwrap()
+        };
+
+        // `{"a": [1, 2, 3], "b": 1}` is 24 characters.
+        assert_eq!(with_width(24), r#"{"a": [1, 2, 3], "b": 1}"#);
+
+        // `  "a": [1, 2, 3]` is 16 characters.
+        let expanded_root = r#"{
+  "a": [1, 2, 3],
+  "b": 1
+}"#;
+        assert_eq!(with_width(23), expanded_root);
+        assert_eq!(with_width(16), expanded_root);
+
+        assert_eq!(
+            with_width(15),
+            r#"{
+  "a": [
+    1,
+    2,
+    3
+  ],
+  "b": 1
+}"#
+        );
+    }
+
+    #[test]
+    fn test_key_priority() {
+        let value =
+            Value::from_json(br#"{"b": 1, "type": "t", "a": {"id": 2, "z": 3, "c": 4}, "id": 5}"#)
+                .unwrap();
+        assert_eq!(
+            value
+                .to_string_with_key_priority(&["id", "type", "missing", "id"])
+                .unwrap(),
+            r#"{"id":5,"type":"t","a":{"id":2,"c":4,"z":3},"b":1}"#
+        );
+        assert_eq!(
+            value.to_string_with_key_priority(&[]).unwrap(),
+            value.to_string()
+        );
+
+        let value = Value::from(vec![Value::Number(Number::non_finite(f64::NAN))]);
+        let err = value.to_string_with_key_priority(&[]).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn test_unquoted_keys() {
+        let value = Value::from_json(br#"{"a_1": 1, "$b": 2, "x y": 3, "1a": 4, "": 5}"#).unwrap();
+        let options = WriteOptions {
+            unquoted_keys: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            value.to_string_with_options(&options).unwrap(),
+            r#"{"":5,$b:2,"1a":4,a_1:1,"x y":3}"#
+        );
+        assert_eq!(value.to_string(), r#"{"":5,"$b":2,"1a":4,"a_1":1,"x y":3}"#);
+    }
+
+    #[test]
+    fn test_log_string() {
+        let value = Value::from_json(br#"{"a": [1, 2], "b": "x\u00e9y"}"#).unwrap();
+        assert_eq!(value.to_log_string(100), value.to_string());
+        assert_eq!(value.to_log_string(22), value.to_string());
+        assert_eq!(value.to_log_string(10), r#"{"a":[1,2]…"#);
+        assert_eq!(value.to_log_string(17), r#"{"a":[1,2],"b":"x…"#);
+        // Doesn't split the two bytes of `é`.
+        assert_eq!(value.to_log_string(18), r#"{"a":[1,2],"b":"x…"#);
+        assert_eq!(value.to_log_string(0), "…");
+
+        let value = Value::from(vec![Value::Number(Number::non_finite(f64::NAN))]);
+        assert_eq!(value.to_log_string(100), "[NaN]");
+
+        let large: Value = (0..100_000).map(Value::from).collect();
+        let log = large.to_log_string(80);
+        assert!(log.len() <= 80 + '…'.len_utf8());
+        assert!(log.starts_with("[0,1,2,3,") && log.ends_with('…'));
+    }
+
+    #[test]
+    fn test_align_values() {
+        let value =
+            Value::from_json(br#"{"a": 1, "long_key": {"b": 2, "cc": [3]}, "mid": null}"#).unwrap();
+        let options = WriteOptions {
+            indent: Some("  ".into()),
+            align_values: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            value.to_string_with_options(&options).unwrap(),
+            r#"{
+  "a"       : 1,
+  "long_key": {
+    "b" : 2,
+    "cc": [
+      3
+    ]
+  },
+  "mid"     : null
+}"#
+        );
+        let options = WriteOptions {
+            align_values: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            value.to_string_with_options(&options).unwrap(),
+            value.to_string()
+        );
+    }
+
+    #[test]
+    fn test_array_writer() {
+        let values = [
+            Value::from(1),
+            "a".into(),
+            Value::from_json(b"{\"b\": [null]}").unwrap(),
+        ];
+
+        let mut writer = ArrayWriter::new(Vec::new());
+        for v in &values {
+            writer.write_element(v).unwrap();
+        }
+        assert_eq!(writer.len(), 3);
+        let bytes = writer.finish().unwrap();
+        assert_eq!(bytes, br#"[1,"a",{"b":[null]}]"#);
+        assert_eq!(Value::from_json(&bytes), Ok(values.to_vec().into()));
+
+        let options = WriteOptions {
+            indent: Some("  ".into()),
+            ..WriteOptions::default()
+        };
+        let mut writer = ArrayWriter::with_options(Vec::new(), options.clone());
+        for v in &values {
+            writer.write_element(v).unwrap();
+        }
+        assert_eq!(
+            String::from_utf8(writer.finish().unwrap()).unwrap(),
+            Value::from(values.to_vec())
+                .to_string_with_options(&options)
+                .unwrap()
+        );
+
+        assert_eq!(ArrayWriter::new(Vec::new()).finish().unwrap(), b"[]");
+    }
+
+    #[test]
+    fn test_non_finite() {
+        let value: Value = vec![
+            Value::Number(Number::non_finite(f64::INFINITY)),
+            Value::Number(Number::non_finite(f64::NEG_INFINITY)),
+            Value::Number(Number::non_finite(f64::NAN)),
+        ]
+        .into();
+        let with_policy = |non_finite| WriteOptions {
+            non_finite,
+            ..WriteOptions::default()
+        };
+
+        let err = value
+            .to_writer_with_options(Vec::new(), &with_policy(NonFinitePolicy::Error))
+            .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        let infinity = Value::Number(Number::non_finite(f64::INFINITY));
+        let err = infinity
+            .to_string_with_options(&WriteOptions::default())
+            .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        let err = infinity
+            .to_bytes_with_options(&WriteOptions::default())
+            .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        let err = infinity.to_string_pretty().unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        let err = infinity.to_writer(Vec::new()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        let err = infinity.to_buffered_writer(Vec::new()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        let err = infinity.to_bytes().unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(
+            value
+                .to_string_with_options(&with_policy(NonFinitePolicy::Null))
+                .unwrap(),
+            "[null,null,null]"
+        );
+        assert_eq!(
+            value
+                .to_string_with_options(&with_policy(NonFinitePolicy::Literal))
+                .unwrap(),
+            "[Infinity,-Infinity,NaN]"
+        );
+    }
+
+    #[test]
+    fn test_integral_float_style() {
+        let value = Value::from_json(b"[2.0, 2, -0.0, 2.5, 1e300]").unwrap();
+
+        assert_eq!(
+            value
+                .to_string_with_options(&WriteOptions::default())
+                .unwrap(),
+            "[2,2,-0,2.5,1e300]"
+        );
+
+        let options = WriteOptions {
+            integral_float_style: IntegralStyle::Dotted,
+            ..WriteOptions::default()
+        };
+        let s = value.to_string_with_options(&options).unwrap();
+        assert_eq!(s, "[2.0,2,-0.0,2.5,1.0e300]");
+        assert_eq!(Value::from_json(s.as_bytes()), Ok(value));
+    }
+
+    #[test]
+    fn test_exponent_threshold() {
+        let value =
+            Value::from_json(b"[1000000.0, 1000000, 999999.5, -2500000.0, 0.0, 0.5, 1.5e-7]")
+                .unwrap();
+        let options = WriteOptions {
+            exponent_threshold: Some(1e6),
+            tiny_exponent_threshold: Some(1e-3),
+            ..WriteOptions::default()
+        };
+        let s = value.to_string_with_options(&options).unwrap();
+        assert_eq!(s, "[1e6,1000000,999999.5,-2.5e6,0,0.5,1.5e-7]");
+        assert_eq!(Value::from_json(s.as_bytes()), Ok(value.clone()));
+
+        let options = WriteOptions {
+            integral_float_style: IntegralStyle::Dotted,
+            ..options
+        };
+        assert_eq!(
+            value.to_string_with_options(&options).unwrap(),
+            "[1.0e6,1000000,999999.5,-2.5e6,0.0,0.5,1.5e-7]"
+        );
+    }
+}
@@ -0,0 +1,180 @@
+use std::ops::Range;
+
+use crate::{
+    array::read_elements,
+    error::ErrorKind,
+    number::Number,
+    object::{read_checked_key, read_members, Object},
+    parser::Reader,
+    read_leaf,
+    string::JsonString,
+    Value,
+};
+
+/// A value together with the byte range of its source text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Spanned<T = SpannedValue> {
+    pub value: T,
+    pub span: Range<usize>,
+}
+
+/// Mirror of [`Value`] where every child carries its source span.
+///
+/// Object members are kept in document order, including duplicate keys.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SpannedValue {
+    Null,
+    Bool(bool),
+    Number(Number),
+    String(JsonString),
+    Array(Vec<Spanned>),
+    Object(Vec<(Spanned<JsonString>, Spanned)>),
+}
+
+impl Spanned {
+    /// Discards the spans. For duplicate object keys the last one wins, like
+    /// [`Value::from_json`].
+    pub fn into_value(self) -> Value {
+        match self.value {
+            SpannedValue::Null => Value::Null,
+            SpannedValue::Bool(v) => Value::Bool(v),
+            SpannedValue::Number(v) => Value::Number(v),
+            SpannedValue::String(v) => Value::String(v),
+            SpannedValue::Array(v) => Value::Array(
+                v.into_iter()
+                    .map(Spanned::into_value)
+                    .collect::<Vec<_>>()
+                    .into(),
+            ),
+            SpannedValue::Object(v) => {
+                let mut obj = Object::new();
+                for (k, v) in v {
+                    obj.insert(k.value, v.into_value());
+                }
+                Value::Object(obj)
+            }
+        }
+    }
+}
+
+impl From<Spanned> for Value {
+    fn from(value: Spanned) -> Self {
+        value.into_value()
+    }
+}
+
+pub(crate) fn read_spanned(reader: &mut Reader) -> Result<Spanned, ErrorKind> {
+    reader.skip_whitespace();
+
+    let start = reader.offset();
+    let Some(b) = reader.peek_byte() else {
+        return Err(ErrorKind::UnexpectedEof);
+    };
+
+    let value = match b {
+        b'[' => {
+            let mut elements = Vec::new();
+            read_elements(reader, |reader| {
+                let value = read_spanned(reader)?;
+                reader.allocate(size_of::<Spanned>())?;
+                elements.push(value);
+                Ok(())
+            })?;
+            SpannedValue::Array(elements)
+        }
+        b'{' => {
+            let mut members = Vec::new();
+            read_members(reader, read_spanned_key, |reader, key| {
+                let value = read_spanned(reader)?;
+                reader.allocate(size_of::<(Spanned<JsonString>, Spanned)>())?;
+                members.push((key, value));
+                Ok(())
+            })?;
+            SpannedValue::Object(members)
+        }
+        _ => match read_leaf(reader, b)? {
+            Value::Null => SpannedValue::Null,
+            Value::Bool(v) => SpannedValue::Bool(v),
+            Value::Number(v) => SpannedValue::Number(v),
+            Value::String(v) => SpannedValue::String(v),
+            Value::Array(_) | Value::Object(_) => unreachable!(),
+        },
+    };
+    let span = start..reader.offset();
+
+    reader.skip_whitespace();
+    Ok(Spanned { value, span })
+}
+
+fn read_spanned_key(reader: &mut Reader) -> Result<Spanned<JsonString>, ErrorKind> {
+    let start = reader.offset();
+    let key = read_checked_key(reader)?;
+    Ok(Spanned {
+        value: key,
+        span: start..reader.offset(),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{Json5, Options};
+
+    #[test]
+    fn test_spans() {
+        let bytes = br#" {"a": [1, "x"], "b" :null} "#;
+        let spanned = Value::from_json_with_spans(bytes).unwrap();
+        assert_eq!(spanned.span, 1..27);
+
+        let SpannedValue::Object(members) = &spanned.value else {
+            panic!("expected object");
+        };
+        let source = |span: &Range<usize>| &bytes[span.clone()];
+
+        assert_eq!(source(&members[0].0.span), br#""a""#);
+        assert_eq!(source(&members[0].1.span), br#"[1, "x"]"#);
+        let SpannedValue::Array(elements) = &members[0].1.value else {
+            panic!("expected array");
+        };
+        assert_eq!(source(&elements[0].span), b"1");
+        assert_eq!(source(&elements[1].span), br#""x""#);
+        assert_eq!(source(&members[1].0.span), br#""b""#);
+        assert_eq!(source(&members[1].1.span), b"null");
+
+        assert_eq!(spanned.into_value(), Value::from_json(bytes).unwrap());
+    }
+
+    #[test]
+    fn test_spans_with_options() {
+        let bytes = b"{a: [1, 'x',], // c\n}";
+        assert!(Value::from_json_with_spans(bytes).is_err());
+
+        let options = Options {
+            dialect: Some(&Json5),
+            ..Options::default()
+        };
+        let spanned = Value::from_json_with_spans_and_options(bytes, &options).unwrap();
+        let SpannedValue::Object(members) = &spanned.value else {
+            panic!("expected object");
+        };
+        assert_eq!(&bytes[members[0].0.span.clone()], b"a");
+        assert_eq!(&bytes[members[0].1.span.clone()], b"[1, 'x',]");
+        assert_eq!(
+            spanned.into_value(),
+            Value::from_json_with_options(bytes, &options).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_spans_keep_duplicate_keys() {
+        let spanned = Value::from_json_with_spans(br#"{"a":1,"a":2}"#).unwrap();
+        let SpannedValue::Object(members) = &spanned.value else {
+            panic!("expected object");
+        };
+        assert_eq!(members.len(), 2);
+        assert_eq!(
+            spanned.into_value(),
+            Value::from_json(br#"{"a":2}"#).unwrap()
+        );
+    }
+}
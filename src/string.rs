@@ -1,9 +1,16 @@
 use core::str;
-use std::{borrow::Borrow, fmt::Display, mem::transmute};
+use std::{
+    borrow::Borrow,
+    fmt::{self, Display, Write},
+    mem::transmute,
+};
 
 use wtf8::{CodePoint, Wtf8, Wtf8Buf};
 
-use crate::{error::Error, parser::Reader};
+use crate::{
+    error::{Error, ErrorKind},
+    parser::Reader,
+};
 
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[repr(transparent)]
@@ -17,6 +24,7 @@ impl JsonStr {
         unsafe { transmute::<&Wtf8, &Self>(wtf8) }
     }
 
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(s: &str) -> &Self {
         Self::from_wtf8(Wtf8::from_str(s))
     }
@@ -40,6 +48,12 @@ impl<'a, 'b: 'a> From<&'b String> for &'a JsonStr {
     }
 }
 
+impl<'a, 'b: 'a> From<&'b JsonString> for &'a JsonStr {
+    fn from(value: &'b JsonString) -> Self {
+        value.borrow()
+    }
+}
+
 /// A JSON string is just a list of 16-bit values.
 ///
 /// They are often valid UTF-16 strings, however they can contain lonely
@@ -58,16 +72,16 @@ impl Default for JsonString {
     }
 }
 
-fn parse_hex_byte(byte: u8) -> Result<u8, Error> {
+fn parse_hex_byte(byte: u8) -> Result<u8, ErrorKind> {
     match byte {
         b'0'..=b'9' => Ok(byte - b'0'),
         b'a'..=b'f' => Ok(byte - b'a' + 10),
         b'A'..=b'F' => Ok(byte - b'A' + 10),
-        _ => Err(Error::InvalidHexChar(byte)),
+        _ => Err(ErrorKind::InvalidHexChar(byte)),
     }
 }
 
-fn parse_hex_escape(bytes: &[u8; 4]) -> Result<u16, Error> {
+fn parse_hex_escape(bytes: &[u8; 4]) -> Result<u16, ErrorKind> {
     let mut r = 0;
     for &b in bytes {
         r *= 16;
@@ -84,21 +98,35 @@ fn u8_to_code_point(v: u8) -> CodePoint {
     CodePoint::from_u32(v.into()).unwrap()
 }
 
-pub(crate) fn read_string(reader: &mut Reader) -> Result<JsonString, Error> {
+/// Reads the contents of a string up to the closing `quote`, or the end of
+/// the input, processing escapes.
+///
+/// Fails with [`ErrorKind::KeyTooLong`] as soon as the contents exceed
+/// `max_len` bytes.
+fn read_string_contents(
+    reader: &mut Reader,
+    quote: u8,
+    max_len: Option<usize>,
+) -> Result<JsonString, ErrorKind> {
     let mut inner = Wtf8Buf::new();
-    match reader.read_byte()? {
-        b'"' => {}
-        b => {
-            return Err(Error::ExpectedDoubleQuote(b));
-        }
-    }
 
-    loop {
-        match reader.peek_byte().ok_or(Error::UnexpectedEof)? {
+    let max_escapes = reader.options().max_escapes_per_string;
+    let mut escapes = 0;
+
+    while let Some(b) = reader.peek_byte() {
+        if max_len.is_some_and(|max| inner.len() > max) {
+            return Err(ErrorKind::KeyTooLong);
+        }
+        match b {
             b'\\' => {
                 reader.read_byte().unwrap();
+                escapes += 1;
+                if max_escapes.is_some_and(|max| escapes > max) {
+                    return Err(ErrorKind::TooManyEscapes);
+                }
                 let v = match reader.read_byte()? {
                     b'"' => b'"',
+                    b'\'' if reader.dialect().single_quoted_strings() => b'\'',
                     b'\\' => b'\\',
                     b'/' => b'/',
                     b'b' => 0x08,
@@ -112,58 +140,221 @@ pub(crate) fn read_string(reader: &mut Reader) -> Result<JsonString, Error> {
                         inner.push(u16_to_code_point(v));
                         continue;
                     }
-                    b => return Err(Error::UnexpectedEscape(b)),
+                    b => return Err(ErrorKind::UnexpectedEscape(b)),
                 };
                 inner.push(u8_to_code_point(v));
             }
-            b'"' => {
-                reader.read_byte().unwrap();
-                break;
-            }
+            b if b == quote => break,
             b => {
                 if b < 0x20 {
-                    return Err(Error::InvalidControlCharacter(b));
+                    return Err(ErrorKind::InvalidControlCharacter(b));
                 }
                 inner.push_char(reader.read_char()?);
             }
         }
     }
 
+    if max_len.is_some_and(|max| inner.len() > max) {
+        return Err(ErrorKind::KeyTooLong);
+    }
+
+    // Surrogates can only come from escapes, and `Wtf8Buf::push` joins
+    // pairs, so any left over are lone.
+    if reader.options().reject_lone_surrogates && inner.as_str().is_none() {
+        return Err(ErrorKind::LoneSurrogate);
+    }
+
+    reader.allocate(inner.len())?;
     Ok(JsonString { inner })
 }
 
-impl Display for JsonString {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "\"")?;
+pub(crate) fn read_string(reader: &mut Reader) -> Result<JsonString, ErrorKind> {
+    read_quoted(reader, None)
+}
 
-        for c in self.inner.code_points() {
-            match c.to_char() {
-                Some(c) => {
-                    let escape_char = match c {
-                        '"' => '"',
-                        '\\' => '\\',
-                        '/' => '/',
-                        '\x08' => 'b',
-                        '\x0c' => 'f',
-                        '\n' => 'n',
-                        '\r' => 'r',
-                        '\t' => 't',
-                        '\x00'..'\x20' => {
-                            write!(f, "\\u{:04x}", u32::from(c))?;
-                            continue;
-                        }
-                        _ => {
-                            write!(f, "{c}")?;
-                            continue;
-                        }
-                    };
-                    write!(f, "\\{escape_char}")?;
+fn read_quoted(reader: &mut Reader, max_len: Option<usize>) -> Result<JsonString, ErrorKind> {
+    let quote = match reader.read_byte()? {
+        b'"' => b'"',
+        b'\'' if reader.dialect().single_quoted_strings() => b'\'',
+        b => {
+            return Err(ErrorKind::ExpectedDoubleQuote(b));
+        }
+    };
+
+    let s = read_string_contents(reader, quote, max_len)?;
+    // The closing quote.
+    reader.read_byte()?;
+    Ok(s)
+}
+
+/// Reads an object key, which the dialect may allow without quotes.
+///
+/// Keys longer than [`Options::max_key_len`] are rejected as soon as the
+/// limit is passed, without reading the rest of the key.
+///
+/// [`Options::max_key_len`]: crate::Options::max_key_len
+pub(crate) fn read_key(reader: &mut Reader) -> Result<JsonString, ErrorKind> {
+    let max_len = reader.options().max_key_len;
+    match reader.peek_byte() {
+        Some(b)
+            if reader.dialect().unquoted_keys()
+                && (b.is_ascii_alphabetic() || b == b'_' || b == b'$') =>
+        {
+            let (key, ()) = reader.parse_slice(|reader| {
+                let mut len = 0;
+                while reader
+                    .peek_byte()
+                    .is_some_and(|b| b.is_ascii_alphanumeric() || b == b'_' || b == b'$')
+                {
+                    len += 1;
+                    if max_len.is_some_and(|max| len > max) {
+                        return Err(ErrorKind::KeyTooLong);
+                    }
+                    reader.read_byte()?;
+                }
+                Ok(())
+            })?;
+            reader.allocate(key.len())?;
+            Ok(JsonStr::from_str(str::from_utf8(key).unwrap()).into())
+        }
+        _ => read_quoted(reader, max_len),
+    }
+}
+
+/// Reads an unquoted string, see [`Options::allow_unquoted_values`].
+///
+/// [`Options::allow_unquoted_values`]: crate::Options::allow_unquoted_values
+pub(crate) fn read_bare_word(reader: &mut Reader) -> Result<JsonString, ErrorKind> {
+    let mut inner = Wtf8Buf::new();
+    while let Some(b) = reader.peek_byte() {
+        match b {
+            b',' | b']' | b'}' => break,
+            b if reader.is_whitespace(b) => break,
+            b if b < 0x20 => return Err(ErrorKind::InvalidControlCharacter(b)),
+            _ => inner.push_char(reader.read_char()?),
+        }
+    }
+    reader.allocate(inner.len())?;
+    Ok(JsonString { inner })
+}
+
+/// Processes the escapes in the contents of a JSON string, given without the
+/// surrounding quotes.
+///
+/// An unescaped `"` results in [`ErrorKind::TrailingData`].
+pub fn unescape(bytes: &[u8]) -> Result<JsonString, Error> {
+    Reader::read_all(bytes, |reader| read_string_contents(reader, b'"', None))
+}
+
+/// Escapes `s` as the contents of a JSON string, without the surrounding
+/// quotes.
+pub fn escape(s: &JsonStr) -> String {
+    let mut out = String::with_capacity(s.inner.len());
+    write_escaped(&mut out, &s.inner).unwrap();
+    out
+}
+
+/// Validates a string like [`read_string`] without building it.
+///
+/// [`Options::max_escapes_per_string`] and [`Options::reject_lone_surrogates`]
+/// are enforced the same way, failing at the same offsets.
+///
+/// [`Options::max_escapes_per_string`]: crate::Options::max_escapes_per_string
+/// [`Options::reject_lone_surrogates`]: crate::Options::reject_lone_surrogates
+pub(crate) fn skip_string(reader: &mut Reader) -> Result<(), ErrorKind> {
+    match reader.read_byte()? {
+        b'"' => {}
+        b => {
+            return Err(ErrorKind::ExpectedDoubleQuote(b));
+        }
+    }
+
+    let max_escapes = reader.options().max_escapes_per_string;
+    let mut escapes = 0;
+    // Whether the last code point was a high surrogate, and whether any
+    // surrogate so far was left unpaired.
+    let mut after_high = false;
+    let mut lone = false;
+
+    loop {
+        let mut high = false;
+        match reader.peek_byte().ok_or(ErrorKind::UnexpectedEof)? {
+            b'\\' => {
+                reader.read_byte().unwrap();
+                escapes += 1;
+                if max_escapes.is_some_and(|max| escapes > max) {
+                    return Err(ErrorKind::TooManyEscapes);
+                }
+                match reader.read_byte()? {
+                    b'"' | b'\\' | b'/' | b'b' | b'f' | b'n' | b'r' | b't' => {}
+                    b'u' => match parse_hex_escape(reader.read_bytes::<4>()?)? {
+                        0xd800..=0xdbff => high = true,
+                        0xdc00..=0xdfff if after_high => after_high = false,
+                        0xdc00..=0xdfff => lone = true,
+                        _ => {}
+                    },
+                    b => return Err(ErrorKind::UnexpectedEscape(b)),
+                }
+            }
+            b'"' => {
+                if reader.options().reject_lone_surrogates && (lone || after_high) {
+                    return Err(ErrorKind::LoneSurrogate);
                 }
-                None => write!(f, "\\u{:04x}", c.to_u32())?,
+                reader.read_byte().unwrap();
+                break;
             }
+            b => {
+                if b < 0x20 {
+                    return Err(ErrorKind::InvalidControlCharacter(b));
+                }
+                reader.read_char()?;
+            }
+        }
+        lone |= after_high;
+        after_high = high;
+    }
+
+    Ok(())
+}
+
+fn write_escaped(f: &mut impl fmt::Write, s: &Wtf8) -> fmt::Result {
+    // Runs of characters that don't need escaping are written in one go.
+    let mut run_start = 0;
+    let mut pos = 0;
+    for c in s.code_points() {
+        let start = pos;
+        pos += c.to_char().map_or(3, char::len_utf8);
+
+        let escape_char = match c.to_char() {
+            Some('"') => '"',
+            Some('\\') => '\\',
+            Some('/') => '/',
+            Some('\x08') => 'b',
+            Some('\x0c') => 'f',
+            Some('\n') => 'n',
+            Some('\r') => 'r',
+            Some('\t') => 't',
+            Some('\x00'..'\x20') | None => 'u',
+            Some(_) => continue,
+        };
+
+        f.write_str(s.slice(run_start, start).as_str().unwrap())?;
+        run_start = pos;
+
+        if escape_char == 'u' {
+            write!(f, "\\u{:04x}", c.to_u32())?;
+        } else {
+            write!(f, "\\{escape_char}")?;
         }
+    }
+    f.write_str(s.slice_from(run_start).as_str().unwrap())
+}
 
-        write!(f, "\"")
+impl Display for JsonString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_char('"')?;
+        write_escaped(f, &self.inner)?;
+        f.write_char('"')
     }
 }
 
@@ -182,6 +373,8 @@ impl JsonString {
         }
     }
 
+    /// Converts to a `String` without copying, or returns `self` if it
+    /// contains a lone surrogate.
     pub fn into_string(self) -> Result<String, Self> {
         self.inner
             .into_string()
@@ -196,15 +389,105 @@ impl JsonString {
         self.inner.as_str()
     }
 
+    /// The number of terminal columns the contents take up when printed,
+    /// with lone surrogates shown as U+FFFD. Control characters count as
+    /// zero.
+    #[cfg(feature = "unicode-width")]
+    pub fn display_width(&self) -> usize {
+        use unicode_width::UnicodeWidthChar;
+
+        self.inner
+            .code_points()
+            .map(|c| c.to_char_lossy().width().unwrap_or(0))
+            .sum()
+    }
+
     pub fn to_ill_formed_utf16(&self) -> impl Iterator<Item = u16> + '_ {
         self.inner.to_ill_formed_utf16()
     }
 
+    /// Compares with `other`, ignoring the case of ASCII letters. Everything
+    /// else, including surrogates, must match exactly.
+    pub fn eq_ignore_ascii_case(&self, other: &JsonStr) -> bool {
+        fn fold(c: CodePoint) -> u32 {
+            match c.to_char() {
+                Some(c) => c.to_ascii_lowercase().into(),
+                None => c.to_u32(),
+            }
+        }
+
+        self.inner.len() == other.inner.len()
+            && self
+                .inner
+                .code_points()
+                .zip(other.inner.code_points())
+                .all(|(a, b)| fold(a) == fold(b))
+    }
+
+    /// Returns the string without leading and trailing whitespace, as defined
+    /// by Unicode. Lone surrogates are never whitespace.
+    pub fn trim(&self) -> &JsonStr {
+        let (start, end) = self.trimmed_range(true, true);
+        JsonStr::from_wtf8(self.inner.slice(start, end))
+    }
+
+    /// Like [`JsonString::trim`], but only removes leading whitespace.
+    pub fn trim_start(&self) -> &JsonStr {
+        let (start, end) = self.trimmed_range(true, false);
+        JsonStr::from_wtf8(self.inner.slice(start, end))
+    }
+
+    /// Like [`JsonString::trim`], but only removes trailing whitespace.
+    pub fn trim_end(&self) -> &JsonStr {
+        let (start, end) = self.trimmed_range(false, true);
+        JsonStr::from_wtf8(self.inner.slice(start, end))
+    }
+
+    /// Byte range of the string without leading and/or trailing whitespace.
+    fn trimmed_range(&self, start: bool, end: bool) -> (usize, usize) {
+        let mut range = None;
+        let mut pos = 0;
+        for c in self.inner.code_points() {
+            let len = c.to_char().map_or(3, char::len_utf8);
+            if !c.to_char().is_some_and(char::is_whitespace) {
+                let (first, _) = range.unwrap_or((pos, pos));
+                range = Some((first, pos + len));
+            }
+            pos += len;
+        }
+        // A string of only whitespace is trimmed to nothing from either end.
+        let Some((first, last)) = range else {
+            return (0, 0);
+        };
+        (if start { first } else { 0 }, if end { last } else { pos })
+    }
+
+    /// Length of the serialized string, including quotes.
+    pub(crate) fn serialized_len(&self) -> usize {
+        2 + self
+            .inner
+            .code_points()
+            .map(|c| match c.to_char() {
+                Some('"' | '\\' | '/' | '\x08' | '\x0c' | '\n' | '\r' | '\t') => 2,
+                Some('\x00'..'\x20') | None => 6,
+                Some(c) => c.len_utf8(),
+            })
+            .sum::<usize>()
+    }
+
     pub(crate) fn as_wtf8(&self) -> &Wtf8 {
         &self.inner
     }
 }
 
+impl From<&JsonStr> for JsonString {
+    fn from(value: &JsonStr) -> Self {
+        let mut inner = Wtf8Buf::with_capacity(value.inner.len());
+        inner.push_wtf8(&value.inner);
+        Self { inner }
+    }
+}
+
 impl From<&str> for JsonString {
     fn from(value: &str) -> Self {
         Self {
@@ -224,6 +507,7 @@ impl From<String> for JsonString {
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::{options::Options, Value};
 
     #[test]
     fn test_unicode() {
@@ -260,4 +544,115 @@ mod test {
             r#""\ud800""#
         );
     }
+
+    #[test]
+    fn test_reject_lone_surrogates() {
+        let options = Options {
+            reject_lone_surrogates: true,
+            ..Options::default()
+        };
+        let parse = |json: &[u8]| Value::from_json_with_options(json, &options);
+        assert_eq!(
+            parse(br#""\ud800""#),
+            Err(Error::at(ErrorKind::LoneSurrogate, 7))
+        );
+        assert_eq!(
+            parse(br#""a\ud800b""#),
+            Err(Error::at(ErrorKind::LoneSurrogate, 9))
+        );
+        assert_eq!(
+            parse(br#""\udc00""#),
+            Err(Error::at(ErrorKind::LoneSurrogate, 7))
+        );
+        assert_eq!(
+            parse(br#"["\udc00\ud800"]"#),
+            Err(Error::at(ErrorKind::LoneSurrogate, 14))
+        );
+        assert_eq!(parse(br#""\ud83d\ude00""#), Ok("\u{1f600}".into()));
+        assert_eq!(parse(r#""é""#.as_bytes()), Ok("é".into()));
+        assert!(Value::from_json(br#""\ud800""#).is_ok());
+    }
+
+    #[cfg(feature = "unicode-width")]
+    #[test]
+    fn test_display_width() {
+        let width = |s: &str| JsonString::from(s).display_width();
+        assert_eq!(width("hello"), 5);
+        assert_eq!(width("日本語"), 6);
+        assert_eq!(width("a\tb"), 2);
+        assert_eq!(
+            JsonString::from_json(br#""a\ud800""#)
+                .unwrap()
+                .display_width(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_unescape_escape() {
+        let s = unescape(br#"a\"b\u00e6\ud800\n"#).unwrap();
+        assert_eq!(
+            s.to_ill_formed_utf16().collect::<Vec<_>>(),
+            [0x61, 0x22, 0x62, 0xe6, 0xd800, 0x0a]
+        );
+        assert_eq!(escape(s.borrow()), r#"a\"bæ\ud800\n"#);
+        assert_eq!(unescape(escape(s.borrow()).as_bytes()), Ok(s));
+
+        assert_eq!(unescape(b""), Ok(JsonString::new()));
+        assert_eq!(
+            unescape(br#"a"b"#),
+            Err(Error::at(ErrorKind::TrailingData, 1))
+        );
+        assert_eq!(
+            unescape(br"a\"),
+            Err(Error::at(ErrorKind::UnexpectedEof, 2))
+        );
+    }
+
+    #[test]
+    fn test_eq_ignore_ascii_case() {
+        assert!(JsonString::from("Foo").eq_ignore_ascii_case("foo".into()));
+        assert!(!JsonString::from("Foo").eq_ignore_ascii_case("fo".into()));
+        assert!(!JsonString::from("Æ").eq_ignore_ascii_case("æ".into()));
+
+        let a = JsonString::from_ill_formed_utf16(&[0x41, 0xd800]);
+        let b = JsonString::from_ill_formed_utf16(&[0x61, 0xd800]);
+        let c = JsonString::from_ill_formed_utf16(&[0x61, 0xd801]);
+        assert!(a.eq_ignore_ascii_case(b.borrow()));
+        assert!(!a.eq_ignore_ascii_case(c.borrow()));
+    }
+
+    #[test]
+    fn test_trim() {
+        let s = JsonString::from_json(br#"" \t\u3000a \ud800 b\n ""#).unwrap();
+        let trimmed = |s: &JsonStr| JsonString::from(s).to_string();
+        assert_eq!(trimmed(s.trim()), r#""a \ud800 b""#);
+        assert_eq!(trimmed(s.trim_start()), r#""a \ud800 b\n ""#);
+        assert_eq!(trimmed(s.trim_end()), "\" \\t\u{3000}a \\ud800 b\"");
+
+        let s = JsonString::from_json(br#""\udc00 ""#).unwrap();
+        assert_eq!(trimmed(s.trim()), r#""\udc00""#);
+        assert_eq!(trimmed(JsonString::from("  ").trim()), r#""""#);
+        assert_eq!(trimmed(JsonString::from("  ").trim_end()), r#""""#);
+    }
+
+    #[test]
+    fn test_max_escapes_per_string() {
+        let options = Options {
+            max_escapes_per_string: Some(2),
+            ..Options::default()
+        };
+        assert_eq!(
+            Value::from_json_with_options(br#""\u0041\n""#, &options),
+            Ok("A\n".into())
+        );
+        assert_eq!(
+            Value::from_json_with_options(br#""\u0041\u0041\u0041""#, &options),
+            Err(Error::at(ErrorKind::TooManyEscapes, 14))
+        );
+        assert_eq!(
+            Value::from_json(br#""\u0041\u0041\u0041""#),
+            Ok("AAA".into())
+        );
+    }
 }
@@ -0,0 +1,93 @@
+use crate::{
+    error::Error,
+    pointer::{push_index, push_key},
+    Value,
+};
+
+/// Error returned by [`assert_round_trips`].
+#[derive(Debug, PartialEq, Eq)]
+pub enum RoundTripError {
+    /// The input is not valid JSON.
+    Parse(Error),
+    /// The serialized value could not be parsed again.
+    Reparse { output: String, error: Error },
+    /// The parsed and re-parsed values differ at this JSON Pointer.
+    Mismatch { output: String, pointer: String },
+}
+
+/// Checks that `bytes` parses to a value that is unchanged by serializing and
+/// parsing it again.
+pub fn assert_round_trips(bytes: &[u8]) -> Result<(), RoundTripError> {
+    let value = Value::from_json(bytes).map_err(RoundTripError::Parse)?;
+    let output = value.to_string();
+    let reparsed = match Value::from_json(output.as_bytes()) {
+        Ok(v) => v,
+        Err(error) => return Err(RoundTripError::Reparse { output, error }),
+    };
+    match first_difference(&value, &reparsed) {
+        Some(pointer) => Err(RoundTripError::Mismatch { output, pointer }),
+        None => Ok(()),
+    }
+}
+
+/// Returns a pointer to the first place where `a` and `b` differ.
+///
+/// Arrays of different lengths and objects with different keys differ as a
+/// whole.
+fn first_difference(a: &Value, b: &Value) -> Option<String> {
+    let mut stack = vec![(a, b, String::new())];
+    while let Some((a, b, path)) = stack.pop() {
+        match (a, b) {
+            (Value::Array(a), Value::Array(b)) if a.len() == b.len() => {
+                for (i, (a, b)) in a.iter().zip(b.iter()).enumerate().rev() {
+                    let mut path = path.clone();
+                    push_index(&mut path, i);
+                    stack.push((a, b, path));
+                }
+            }
+            (Value::Object(a), Value::Object(b)) if a.as_inner().keys().eq(b.as_inner().keys()) => {
+                for ((k, a), b) in a.as_inner().iter().zip(b.as_inner().values()).rev() {
+                    let mut path = path.clone();
+                    push_key(&mut path, k);
+                    stack.push((a, b, path));
+                }
+            }
+            (a, b) if a != b => return Some(path),
+            _ => {}
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::error::ErrorKind;
+
+    #[test]
+    fn test_assert_round_trips() {
+        assert_eq!(
+            assert_round_trips(br#"{"a": [1, 2.5e10, "\ud800"], "b": {}}"#),
+            Ok(())
+        );
+        assert_eq!(
+            assert_round_trips(b"[1,"),
+            Err(RoundTripError::Parse(Error::at(
+                ErrorKind::UnexpectedEof,
+                3
+            )))
+        );
+    }
+
+    #[test]
+    fn test_first_difference() {
+        let a = Value::from_json(br#"{"a/b": [1, {"c": 2}], "d": [1]}"#).unwrap();
+        let b = Value::from_json(br#"{"a/b": [1, {"c": 3}], "d": [1, 2]}"#).unwrap();
+        assert_eq!(first_difference(&a, &a), None);
+        assert_eq!(first_difference(&a, &b), Some("/a~1b/1/c".to_owned()));
+        assert_eq!(first_difference(&b["d"], &a["d"]), Some("".to_owned()));
+
+        let c = Value::from_json(br#"{"a/b": [1, {"e": 2}], "d": [1]}"#).unwrap();
+        assert_eq!(first_difference(&a, &c), Some("/a~1b/1".to_owned()));
+    }
+}